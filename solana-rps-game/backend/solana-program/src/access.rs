@@ -0,0 +1,68 @@
+// Access control for private games. The host fixes either an allowlist of
+// invited wallets or the public key of a keypair derived from a join code.
+// Players prove they know the code by having that keypair co-sign their join,
+// so the code itself never appears on chain and a seen join cannot be replayed
+// for another wallet. The keypair is derived from the game too, so a code shared
+// for one game never opens another. Codes should still be long and random: the
+// key can be checked against guessed codes offline.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{hash::hashv, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::RpsError;
+
+// Domain tag prefixed to every join code seed preimage
+pub const JOIN_CODE_DOMAIN: &[u8] = b"rps-join-code";
+
+// Most wallets a host can invite
+pub const MAX_INVITES: usize = 8;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum GameAccess {
+    // Anyone can join while there is room
+    Open,
+    // Only the listed wallets can join
+    Allowlist(Vec<Pubkey>),
+    // Only players whose join is co-signed by this key, see `join_code_seed`, can join
+    JoinCode(Pubkey),
+}
+
+impl GameAccess {
+    // Serialized size of the largest access rule
+    pub const MAX_LEN: usize = 1 + 4 + MAX_INVITES * 32;
+
+    pub fn is_private(&self) -> bool {
+        !matches!(self, GameAccess::Open)
+    }
+
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        let valid = match self {
+            GameAccess::Open => true,
+            GameAccess::Allowlist(invited) => !invited.is_empty() && invited.len() <= MAX_INVITES,
+            GameAccess::JoinCode(code_key) => *code_key != Pubkey::default(),
+        };
+
+        if !valid {
+            return Err(RpsError::InvalidGameAccess.into());
+        }
+        Ok(())
+    }
+
+    // Check that `player` may join, given the join code key that co-signed the join, if any
+    pub fn admit(&self, player: &Pubkey, code_signer: Option<&Pubkey>) -> Result<(), ProgramError> {
+        match self {
+            GameAccess::Open => Ok(()),
+            GameAccess::Allowlist(invited) if invited.contains(player) => Ok(()),
+            GameAccess::Allowlist(_) => Err(RpsError::NotInvited.into()),
+            GameAccess::JoinCode(code_key) if code_signer == Some(code_key) => Ok(()),
+            GameAccess::JoinCode(_) => Err(RpsError::InvalidJoinCode.into()),
+        }
+    }
+}
+
+// Seed of the keypair standing for `code` in `game`. Clients turn it into a
+// keypair (e.g. `keypair_from_seed`); the host stores its public key in
+// `GameAccess::JoinCode` and joiners have it sign `JoinGameWithCode`.
+pub fn join_code_seed(game: &Pubkey, code: &[u8]) -> [u8; 32] {
+    hashv(&[JOIN_CODE_DOMAIN, game.as_ref(), code]).to_bytes()
+}
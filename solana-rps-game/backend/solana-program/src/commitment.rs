@@ -0,0 +1,43 @@
+// Commitment scheme for the commit/reveal phases. The preimage binds the
+// commitment to one game, round and player, so a commitment copied from
+// another player, round or game never verifies.
+
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+use crate::Choice;
+
+// Domain tag prefixed to every commitment preimage
+pub const COMMITMENT_DOMAIN: &[u8] = b"rps-commitment";
+
+// Bumped whenever the preimage layout changes
+pub const COMMITMENT_VERSION: u8 = 1;
+
+// Byte encoding of a playable choice: its 1-based index, which covers every
+// rule set. `Choice::NONE` cannot be committed.
+pub fn choice_byte(choice: &Choice) -> Option<u8> {
+    match *choice {
+        Choice::NONE => None,
+        choice => Some(choice.index()),
+    }
+}
+
+// Hash a player commits to for `choice` in `round` of `game`.
+// Clients call this to build `CommitChoice`; the program recomputes it on reveal.
+pub fn compute_commitment(
+    game: &Pubkey,
+    round: u8,
+    player: &Pubkey,
+    choice: u8,
+    salt: &[u8; 32],
+) -> [u8; 32] {
+    hashv(&[
+        COMMITMENT_DOMAIN,
+        &[COMMITMENT_VERSION],
+        game.as_ref(),
+        &[round],
+        player.as_ref(),
+        &[choice],
+        salt,
+    ])
+    .to_bytes()
+}
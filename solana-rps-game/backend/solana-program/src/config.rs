@@ -0,0 +1,233 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+use crate::{error::RpsError, pay_out, Game, GameState};
+
+// Seed of the global program configuration PDA
+pub const CONFIG_SEED: &[u8] = b"config";
+
+// Upper bound on the protocol fee (10%)
+pub const MAX_FEE_BASIS_POINTS: u16 = 1_000;
+
+pub const BASIS_POINTS_DENOMINATOR: u64 = 10_000;
+
+// Shortest time winners get to claim before a settled game can be closed (one day)
+pub const MIN_GAME_EXPIRY_SECONDS: u64 = 86_400;
+
+// Global program configuration, stored in a PDA derived from CONFIG_SEED
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ProgramConfig {
+    pub admin: Pubkey,            // Authority allowed to update the configuration
+    pub treasury: Pubkey,         // Wallet receiving the protocol fees
+    pub fee_basis_points: u16,    // Fee taken from each pot at settlement
+    pub game_expiry_seconds: u64, // Idle time after which settled games close with unclaimed funds, snapshotted by each game
+    pub bump: u8,                 // Bump seed of the config PDA
+}
+
+impl ProgramConfig {
+    pub const LEN: usize = 32 + // admin
+        32 + // treasury
+        2 +  // fee_basis_points
+        8 +  // game_expiry_seconds
+        1;   // bump
+
+    // Load the configuration, ensuring the account is the program's config PDA
+    pub fn load(program_id: &Pubkey, config_account: &AccountInfo) -> Result<Self, ProgramError> {
+        if config_account.owner != program_id {
+            return Err(RpsError::InvalidConfigAccount.into());
+        }
+
+        let config = Self::deserialize(&mut &config_account.data.borrow()[..])?;
+
+        let expected = Pubkey::create_program_address(&[CONFIG_SEED, &[config.bump]], program_id)
+            .map_err(|_| RpsError::InvalidConfigAccount)?;
+        if expected != *config_account.key {
+            return Err(RpsError::InvalidConfigAccount.into());
+        }
+
+        Ok(config)
+    }
+
+    pub fn save(&self, config_account: &AccountInfo) -> ProgramResult {
+        self.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+        Ok(())
+    }
+}
+
+// Derive the global configuration PDA
+pub fn find_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+// Fee owed to the treasury on a pot of `amount`
+pub fn calculate_fee(amount: u64, fee_basis_points: u16) -> u64 {
+    (amount as u128 * fee_basis_points as u128 / BASIS_POINTS_DENOMINATOR as u128) as u64
+}
+
+fn validate_fee_basis_points(fee_basis_points: u16) -> ProgramResult {
+    if fee_basis_points > MAX_FEE_BASIS_POINTS {
+        return Err(RpsError::InvalidFeeBasisPoints.into());
+    }
+    Ok(())
+}
+
+fn validate_game_expiry(game_expiry_seconds: u64) -> ProgramResult {
+    if game_expiry_seconds < MIN_GAME_EXPIRY_SECONDS {
+        return Err(RpsError::GameExpiryTooShort.into());
+    }
+    Ok(())
+}
+
+// Implementation for creating the global configuration.
+// The signer becomes the admin.
+pub fn process_initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_basis_points: u16,
+    treasury: Pubkey,
+    game_expiry_seconds: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // Ensure the admin signed the transaction
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    validate_fee_basis_points(fee_basis_points)?;
+    validate_game_expiry(game_expiry_seconds)?;
+
+    let (config_key, bump) = find_config_address(program_id);
+    if config_key != *config_account.key {
+        return Err(RpsError::InvalidConfigAccount.into());
+    }
+
+    let rent = Rent::get()?;
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            config_account.key,
+            rent.minimum_balance(ProgramConfig::LEN),
+            ProgramConfig::LEN as u64,
+            program_id,
+        ),
+        &[admin.clone(), config_account.clone(), system_program.clone()],
+        &[&[CONFIG_SEED, &[bump]]],
+    )?;
+
+    let config = ProgramConfig {
+        admin: *admin.key,
+        treasury,
+        fee_basis_points,
+        game_expiry_seconds,
+        bump,
+    };
+    config.save(config_account)?;
+
+    msg!("Program config initialized, fee: {} bps", fee_basis_points);
+
+    Ok(())
+}
+
+// Implementation for updating the global configuration
+pub fn process_update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_admin: Pubkey,
+    fee_basis_points: u16,
+    treasury: Pubkey,
+    game_expiry_seconds: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+
+    // Ensure the admin signed the transaction
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut config = ProgramConfig::load(program_id, config_account)?;
+
+    if config.admin != *admin.key {
+        return Err(RpsError::NotConfigAdmin.into());
+    }
+
+    validate_fee_basis_points(fee_basis_points)?;
+    validate_game_expiry(game_expiry_seconds)?;
+
+    config.admin = new_admin;
+    config.fee_basis_points = fee_basis_points;
+    config.treasury = treasury;
+    config.game_expiry_seconds = game_expiry_seconds;
+    config.save(config_account)?;
+
+    msg!("Program config updated, fee: {} bps", fee_basis_points);
+
+    Ok(())
+}
+
+// Implementation for sending a finished game's collected fees to the treasury
+pub fn process_withdraw_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let authority = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+
+    // Ensure the authority signed the transaction
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let config = ProgramConfig::load(program_id, config_account)?;
+
+    // Either the admin or the treasury itself may sweep fees
+    if *authority.key != config.admin && *authority.key != config.treasury {
+        return Err(RpsError::NotConfigAdmin.into());
+    }
+
+    if *treasury.key != config.treasury {
+        return Err(RpsError::InvalidTreasury.into());
+    }
+
+    let mut game = Game::load(program_id, game_account)?;
+    game.check_vault(program_id, game_account.key, vault)?;
+
+    if game.state != GameState::Finished {
+        return Err(RpsError::GameNotFinished.into());
+    }
+
+    let amount = game.fees_owed;
+    if amount == 0 {
+        return Err(RpsError::NoFeesToWithdraw.into());
+    }
+
+    game.fees_owed = 0;
+    game.save(game_account)?;
+
+    pay_out(&game, game_account.key, vault, treasury, accounts_iter, amount)?;
+
+    msg!("Withdrew {} in fees to treasury {}", amount, treasury.key);
+
+    Ok(())
+}
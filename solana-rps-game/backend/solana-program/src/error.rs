@@ -0,0 +1,239 @@
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+// Errors that may be returned by the RPS program.
+// The discriminant of each variant is the `Custom` error code seen by clients,
+// so new variants must only ever be appended.
+#[derive(Clone, Copy, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum RpsError {
+    // Game configuration
+    #[error("Invalid player count: duels need 2 players, other games 3 or 4")]
+    InvalidPlayerCount,
+    #[error("Total rounds must be positive, and odd for duels")]
+    InvalidTotalRounds,
+    #[error("Unknown game mode")]
+    InvalidGameMode,
+    #[error("Unknown currency mode")]
+    InvalidCurrencyMode,
+
+    // Game state
+    #[error("Game is not accepting players")]
+    GameNotJoinable,
+    #[error("Game is not in the commit phase")]
+    NotCommitPhase,
+    #[error("Game is not in the reveal phase")]
+    NotRevealPhase,
+    #[error("Game has not finished yet")]
+    GameNotFinished,
+    #[error("Game has already finished")]
+    GameAlreadyFinished,
+    #[error("Game is not in automated mode")]
+    NotAutomatedGame,
+    #[error("Maximum number of automated rounds reached")]
+    MaxAutoRoundsReached,
+    #[error("Timeout has not elapsed yet")]
+    TimeoutNotReached,
+
+    // Players
+    #[error("Game is full")]
+    GameFull,
+    #[error("Player already joined this game")]
+    PlayerAlreadyJoined,
+    #[error("Player is not part of this game")]
+    PlayerNotInGame,
+    #[error("Signer is neither the host nor a player of this game")]
+    NotAParticipant,
+
+    // Commit / reveal
+    #[error("Revealed choice is not a valid move")]
+    InvalidChoice,
+    #[error("Revealed choice and salt do not match the commitment")]
+    CommitmentMismatch,
+
+    // Payouts and rejoining
+    #[error("Player is not among the winners")]
+    NotAWinner,
+    #[error("Losers are not allowed to rejoin this game")]
+    RejoinNotAllowed,
+    #[error("Only players who lost the previous game can rejoin")]
+    NotALoser,
+
+    // Accounts and escrow
+    #[error("Account is not a game owned by the RPS program")]
+    InvalidGameAccount,
+    #[error("Vault account does not match the game's vault PDA")]
+    InvalidVault,
+    #[error("Vault does not hold enough lamports for this payout")]
+    InsufficientVaultFunds,
+    #[error("Mint is not an SPL Token mint")]
+    InvalidMint,
+    #[error("Token account is not the expected associated token account")]
+    InvalidTokenAccount,
+
+    // Protocol config and fees
+    #[error("Config account is not the program's config PDA")]
+    InvalidConfigAccount,
+    #[error("Fee basis points exceed the allowed maximum")]
+    InvalidFeeBasisPoints,
+    #[error("Signer is not the config admin")]
+    NotConfigAdmin,
+    #[error("Treasury account does not match the config")]
+    InvalidTreasury,
+    #[error("Game has no fees left to withdraw")]
+    NoFeesToWithdraw,
+
+    // Refunds
+    #[error("Game was cancelled")]
+    GameCancelled,
+    #[error("Game has not been cancelled")]
+    GameNotCancelled,
+    #[error("Player has already claimed")]
+    AlreadyClaimed,
+
+    // Closing games
+    #[error("Host account does not match the game's host")]
+    InvalidHost,
+    #[error("Game still has unclaimed payouts and has not expired")]
+    PayoutsPending,
+
+    // Phase deadlines
+    #[error("Commit and reveal timeouts must be greater than zero")]
+    InvalidPhaseTimeout,
+    #[error("Commit deadline has passed")]
+    CommitDeadlinePassed,
+    #[error("Reveal deadline has passed")]
+    RevealDeadlinePassed,
+
+    // Randomness
+    #[error("Account is not a populated SlotHashes sysvar")]
+    InvalidSlotHashes,
+
+    // Tournaments
+    #[error("Tournament account is not owned by the RPS program")]
+    InvalidTournamentAccount,
+    #[error("Bracket size must be a power of two between 2 and 64")]
+    InvalidBracketSize,
+    #[error("Prize table must sum to 10000 basis points with at most one share per place")]
+    InvalidPrizeTable,
+    #[error("Tournament is not open for registration")]
+    TournamentNotOpen,
+    #[error("Tournament is not in progress")]
+    TournamentNotInProgress,
+    #[error("Every match of the current bracket round is already open")]
+    NoMatchToOpen,
+    #[error("Match account does not belong to the tournament's current round")]
+    InvalidMatchAccount,
+    #[error("Match has not finished")]
+    MatchNotFinished,
+    #[error("Signer is not the tournament organizer")]
+    NotTournamentOrganizer,
+    #[error("Tournament matches cannot be restarted or closed directly")]
+    TournamentMatch,
+
+    // Battle royale
+    #[error("Battle royale survivors must be at least one and fewer than the minimum players")]
+    InvalidSurvivorCount,
+    #[error("Player has been eliminated")]
+    PlayerEliminated,
+
+    // Duels
+    #[error("Only a duel can name an opponent, and not its own host")]
+    InvalidOpponent,
+    #[error("Duel is reserved for another opponent")]
+    NotInvitedOpponent,
+
+    // Rule sets
+    #[error("Rule set must give an odd number of choices each beating half of the others")]
+    InvalidRuleSet,
+
+    // Payout tables
+    #[error("Payout shares must sum to 10000 basis points and be ranked highest first")]
+    InvalidPayoutTable,
+
+    // Player profiles
+    #[error("Account is not a player profile")]
+    InvalidProfileAccount,
+    #[error("Player profile was passed more than once")]
+    DuplicateProfile,
+
+    // Ratings
+    #[error("Rating band must not have its minimum above its maximum")]
+    InvalidRatingBand,
+    #[error("Player rating is outside the game's rating band")]
+    RatingOutOfBand,
+
+    // Open lobby
+    #[error("Account is not the open-lobby registry")]
+    InvalidLobbyAccount,
+    #[error("Open lobby has no room for another game")]
+    LobbyFull,
+
+    // Private games
+    #[error("Allowlist must name between one and eight wallets, and join code keys cannot be the default key")]
+    InvalidGameAccess,
+    #[error("Player is not invited to this private game")]
+    NotInvited,
+    #[error("Join was not signed by the game's join code key")]
+    InvalidJoinCode,
+    #[error("Private games cannot be listed in the open lobby")]
+    PrivateGameListed,
+
+    // Tournament registration
+    #[error("Tournament registration timeout must be greater than zero")]
+    InvalidRegistrationTimeout,
+
+    // Settlement
+    #[error("Settling a game needs the profile address of every human player")]
+    MissingProfile,
+
+    // Lobby listings
+    #[error("Listed games cannot wait for players longer than an hour")]
+    ListedTimeoutTooLong,
+
+    // Player count seed
+    #[error("Lobbies with a range of sizes need a player count seed hash, fixed sizes none")]
+    InvalidCountSeedHash,
+    #[error("Seed does not match the game's player count seed hash")]
+    CountSeedMismatch,
+    #[error("Player count seed is revealed once the minimum number of players is seated")]
+    CountSeedNotDue,
+
+    // Duel scoring
+    #[error("Duels can only be scored pairwise or by round winners")]
+    InvalidDuelScoring,
+
+    // Game expiry
+    #[error("Game expiry must be at least one day")]
+    GameExpiryTooShort,
+
+    // Player count seed deadline
+    #[error("Player count seed was not revealed in time; the lobby can only be cancelled")]
+    CountSeedDeadlinePassed,
+}
+
+impl From<RpsError> for ProgramError {
+    fn from(e: RpsError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for RpsError {
+    fn type_of() -> &'static str {
+        "RpsError"
+    }
+}
+
+impl PrintProgramError for RpsError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
@@ -0,0 +1,200 @@
+// Structured events for indexers. Each event is logged with `sol_log_data` as
+// three fields: the event discriminator, the schema version and the Borsh
+// encoded `GameEvent`. Variants are only ever appended; the version is bumped
+// when the layout of an existing variant changes. Off-chain services rebuild a
+// game's timeline by running `parse_logs` over the logs of successful
+// transactions, in slot order.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{entrypoint::ProgramResult, log::sol_log_data, pubkey::Pubkey};
+
+use crate::{Choice, CurrencyMode, Game, GameMode, Player};
+
+// First field of every event record, telling them apart from other programs' data
+pub const EVENT_DISCRIMINATOR: &[u8; 8] = b"rps:evnt";
+
+// Version of the event layout emitted by this build
+pub const EVENT_VERSION: u8 = 1;
+
+// A seat's outcome in a resolved round
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SeatResult {
+    pub player: Pubkey,
+    pub choice: Choice, // NONE for players who did not reveal or sat the round out
+    pub score: u8,      // Score after the round
+    pub eliminated: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Payout {
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    // Followed by a PlayerJoined for every seat taken at creation
+    GameCreated {
+        game: Pubkey,
+        host: Pubkey,
+        currency_mode: CurrencyMode,
+        game_mode: GameMode,
+        entry_fee: u64,
+        min_players: u8,
+        max_players: u8,
+        total_rounds: u8,
+    },
+    // Also emitted for bots and for losers rejoining a finished game
+    PlayerJoined {
+        game: Pubkey,
+        player: Pubkey,
+        is_bot: bool,
+    },
+    Committed {
+        game: Pubkey,
+        player: Pubkey,
+        round: u8,
+    },
+    Revealed {
+        game: Pubkey,
+        player: Pubkey,
+        round: u8,
+        choice: Choice,
+    },
+    RoundResolved {
+        game: Pubkey,
+        round: u8,
+        seats: Vec<SeatResult>,
+    },
+    // Payouts frozen at settlement, after the protocol fee
+    GameFinished {
+        game: Pubkey,
+        payouts: Vec<Payout>,
+    },
+    Claimed {
+        game: Pubkey,
+        player: Pubkey,
+        amount: u64,
+    },
+    Refunded {
+        game: Pubkey,
+        player: Pubkey,
+        amount: u64,
+    },
+}
+
+impl GameEvent {
+    pub(crate) fn game_created(game_key: &Pubkey, game: &Game) -> Self {
+        GameEvent::GameCreated {
+            game: *game_key,
+            host: game.host,
+            currency_mode: game.currency_mode.clone(),
+            game_mode: game.game_mode.clone(),
+            entry_fee: game.entry_fee,
+            min_players: game.min_players,
+            max_players: game.max_players,
+            total_rounds: game.total_rounds,
+        }
+    }
+
+    pub(crate) fn player_joined(game_key: &Pubkey, player: &Player) -> Self {
+        GameEvent::PlayerJoined { game: *game_key, player: player.pubkey, is_bot: player.is_bot }
+    }
+
+    pub(crate) fn round_resolved(game_key: &Pubkey, game: &Game) -> Self {
+        let seats = game.players
+            .iter()
+            .map(|p| SeatResult {
+                player: p.pubkey,
+                choice: p.choice,
+                score: p.score,
+                eliminated: p.eliminated,
+            })
+            .collect();
+
+        GameEvent::RoundResolved { game: *game_key, round: game.current_round, seats }
+    }
+
+    pub(crate) fn game_finished(game_key: &Pubkey, game: &Game) -> Self {
+        let payouts = game.players
+            .iter()
+            .filter(|p| p.winnings > 0)
+            .map(|p| Payout { player: p.pubkey, amount: p.winnings })
+            .collect();
+
+        GameEvent::GameFinished { game: *game_key, payouts }
+    }
+
+    pub(crate) fn emit(&self) -> ProgramResult {
+        let payload = self.try_to_vec()?;
+        sol_log_data(&[EVENT_DISCRIMINATOR, &[EVENT_VERSION], &payload]);
+        Ok(())
+    }
+
+    // Decode the fields of one `sol_log_data` record; records of other programs,
+    // other versions or malformed payloads yield None
+    pub fn decode(fields: &[&[u8]]) -> Option<Self> {
+        match fields {
+            [discriminator, version, payload]
+                if *discriminator == EVENT_DISCRIMINATOR && *version == [EVENT_VERSION] =>
+            {
+                Self::try_from_slice(payload).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+// Extract the events emitted by `program_id` from a transaction's log messages,
+// in emission order. Invocations are tracked so that data logged by other
+// programs, including programs this one calls, is skipped.
+pub fn parse_logs<S: AsRef<str>>(program_id: &Pubkey, logs: &[S]) -> Vec<GameEvent> {
+    let program = program_id.to_string();
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        let line = line.as_ref();
+
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            if invocations.last() != Some(&program.as_str()) {
+                continue;
+            }
+
+            let fields: Option<Vec<Vec<u8>>> = data
+                .split_whitespace()
+                .map(|field| STANDARD.decode(field).ok())
+                .collect();
+            let Some(fields) = fields else {
+                continue;
+            };
+
+            let fields: Vec<&[u8]> = fields.iter().map(Vec::as_slice).collect();
+            events.extend(GameEvent::decode(&fields));
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        // Skip "Program log:", "Program return:" and the like
+        let mut words = rest.split_whitespace();
+        let (Some(id), Some(status)) = (words.next(), words.next()) else {
+            continue;
+        };
+        if id.ends_with(':') {
+            continue;
+        }
+
+        match status {
+            "invoke" => invocations.push(id),
+            "success" | "failed:" => {
+                invocations.pop();
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
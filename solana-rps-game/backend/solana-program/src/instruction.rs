@@ -0,0 +1,758 @@
+// Builders for every `RPSInstruction`, filling in the account metas in the
+// order the processors expect. Token games pass `Some(mint)` and the
+// associated token accounts are derived from it.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+    sysvar::slot_hashes,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::{
+    access::GameAccess, config::find_config_address, find_vault_address, lobby::find_lobby_address,
+    payouts::PayoutTable, profile::find_profile_address, rules::RuleSet, scoring::ScoringModel,
+    tournament::{find_match_address, find_tournament_vault_address}, Choice, RPSInstruction,
+};
+
+fn rps_instruction(program_id: Pubkey, instruction: &RPSInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+    // Serializing an `RPSInstruction` into a Vec cannot fail
+    Instruction::new_with_bytes(program_id, &instruction.try_to_vec().unwrap(), accounts)
+}
+
+// Owner token account, vault token account and token program, appended for RPSToken games
+fn token_accounts(owner: &Pubkey, vault: &Pubkey, mint: Option<&Pubkey>) -> Vec<AccountMeta> {
+    match mint {
+        Some(mint) => vec![
+            AccountMeta::new(get_associated_token_address(owner, mint), false),
+            AccountMeta::new(get_associated_token_address(vault, mint), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        None => vec![],
+    }
+}
+
+// Parameters of a new game, mirroring `RPSInstruction::InitializeGame` minus
+// the currency mode. The default is an open three-player, one-round SOL game
+// with free entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameSettings {
+    pub min_players: u8,
+    pub max_players: u8,
+    pub total_rounds: u8,
+    pub entry_fee: u64,
+    pub timeout_seconds: u64,
+    pub losers_can_rejoin: bool,
+    pub game_mode: u8,
+    pub auto_round_delay: u64,
+    pub max_auto_rounds: u64,
+    pub commit_timeout_seconds: u64,
+    pub reveal_timeout_seconds: u64,
+    pub survivors: u8,
+    pub opponent: Pubkey,
+    pub rule_set: RuleSet,
+    pub scoring_model: ScoringModel,
+    pub payout_table: PayoutTable,
+    pub min_rating: u16,
+    pub max_rating: u16,
+    pub access: GameAccess,
+    pub count_seed_hash: [u8; 32],
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        GameSettings {
+            min_players: 3,
+            max_players: 3,
+            total_rounds: 1,
+            entry_fee: 0,
+            timeout_seconds: 300,
+            losers_can_rejoin: false,
+            game_mode: 0,
+            auto_round_delay: 0,
+            max_auto_rounds: 0,
+            commit_timeout_seconds: 300,
+            reveal_timeout_seconds: 300,
+            survivors: 0,
+            opponent: Pubkey::default(),
+            rule_set: RuleSet::Classic,
+            scoring_model: ScoringModel::Pairwise,
+            payout_table: PayoutTable::WinnerTakeAll,
+            min_rating: 0,
+            max_rating: 0,
+            access: GameAccess::Open,
+            count_seed_hash: [0; 32],
+        }
+    }
+}
+
+impl GameSettings {
+    fn into_instruction(self, currency_mode: u8) -> RPSInstruction {
+        RPSInstruction::InitializeGame {
+            min_players: self.min_players,
+            max_players: self.max_players,
+            total_rounds: self.total_rounds,
+            entry_fee: self.entry_fee,
+            timeout_seconds: self.timeout_seconds,
+            losers_can_rejoin: self.losers_can_rejoin,
+            game_mode: self.game_mode,
+            currency_mode,
+            auto_round_delay: self.auto_round_delay,
+            max_auto_rounds: self.max_auto_rounds,
+            commit_timeout_seconds: self.commit_timeout_seconds,
+            reveal_timeout_seconds: self.reveal_timeout_seconds,
+            survivors: self.survivors,
+            opponent: self.opponent,
+            rule_set: self.rule_set,
+            scoring_model: self.scoring_model,
+            payout_table: self.payout_table,
+            min_rating: self.min_rating,
+            max_rating: self.max_rating,
+            access: self.access,
+            count_seed_hash: self.count_seed_hash,
+        }
+    }
+}
+
+// Create a game hosted by `initializer`; the host pays the first entry fee.
+// The currency mode follows from `mint`.
+pub fn initialize_game(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    game: &Pubkey,
+    mint: Option<&Pubkey>,
+    settings: GameSettings,
+) -> Instruction {
+    let (vault, _) = find_vault_address(game, program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*initializer, true),
+        AccountMeta::new(*game, true),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(find_config_address(program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(mint) = mint {
+        accounts.extend([
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_associated_token_address(initializer, mint), false),
+            AccountMeta::new(get_associated_token_address(&vault, mint), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ]);
+    }
+
+    rps_instruction(*program_id, &settings.into_instruction(mint.is_some() as u8), accounts)
+}
+
+pub fn join_game(program_id: &Pubkey, player: &Pubkey, game: &Pubkey, mint: Option<&Pubkey>) -> Instruction {
+    let (vault, _) = find_vault_address(game, program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*player, true),
+        AccountMeta::new(*game, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(token_accounts(player, &vault, mint));
+
+    rps_instruction(*program_id, &RPSInstruction::JoinGame, accounts)
+}
+
+// Turn a JoinGame instruction, rated or not, into one co-signed by a private game's
+// join code key, see access::join_code_seed
+pub fn with_join_code(instruction: Instruction, code_key: &Pubkey) -> Instruction {
+    let mut accounts = instruction.accounts;
+    accounts.insert(4, AccountMeta::new_readonly(*code_key, true));
+    rps_instruction(instruction.program_id, &RPSInstruction::JoinGameWithCode, accounts)
+}
+
+// Join a game with a rating band, proving the player's rating with their profile
+pub fn join_rated_game(program_id: &Pubkey, player: &Pubkey, game: &Pubkey, mint: Option<&Pubkey>) -> Instruction {
+    let mut instruction = join_game(program_id, player, game, mint);
+    let (profile, _) = find_profile_address(player, program_id);
+    instruction.accounts.insert(4, AccountMeta::new_readonly(profile, false));
+    instruction
+}
+
+// Reveal the host's player count seed, see randomness::count_seed_hash
+pub fn reveal_count_seed(program_id: &Pubkey, host: &Pubkey, game: &Pubkey, seed: [u8; 32]) -> Instruction {
+    rps_instruction(*program_id, &RPSInstruction::RevealCountSeed { seed }, player_and_game(host, game))
+}
+
+pub fn commit_choice(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    game: &Pubkey,
+    committed_choice: [u8; 32],
+) -> Instruction {
+    rps_instruction(
+        *program_id,
+        &RPSInstruction::CommitChoice { committed_choice },
+        with_slot_hashes(player, game),
+    )
+}
+
+pub fn reveal_choice(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    game: &Pubkey,
+    choice: Choice,
+    salt: [u8; 32],
+) -> Instruction {
+    rps_instruction(
+        *program_id,
+        &RPSInstruction::RevealChoice { choice, salt },
+        player_and_game(player, game),
+    )
+}
+
+pub fn resolve_timeout(program_id: &Pubkey, caller: &Pubkey, game: &Pubkey) -> Instruction {
+    rps_instruction(
+        *program_id,
+        &RPSInstruction::ResolveTimeout,
+        with_slot_hashes(caller, game),
+    )
+}
+
+pub fn claim_winnings(program_id: &Pubkey, winner: &Pubkey, game: &Pubkey, mint: Option<&Pubkey>) -> Instruction {
+    let (vault, _) = find_vault_address(game, program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*winner, true),
+        AccountMeta::new(*game, false),
+        AccountMeta::new(vault, false),
+    ];
+    accounts.extend(token_accounts(winner, &vault, mint));
+
+    rps_instruction(*program_id, &RPSInstruction::ClaimWinnings, accounts)
+}
+
+pub fn rejoin_game(program_id: &Pubkey, player: &Pubkey, game: &Pubkey, mint: Option<&Pubkey>) -> Instruction {
+    let (vault, _) = find_vault_address(game, program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*player, true),
+        AccountMeta::new(*game, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(token_accounts(player, &vault, mint));
+
+    rps_instruction(*program_id, &RPSInstruction::RejoinGame, accounts)
+}
+
+pub fn start_new_game_round(program_id: &Pubkey, initiator: &Pubkey, game: &Pubkey) -> Instruction {
+    rps_instruction(
+        *program_id,
+        &RPSInstruction::StartNewGameRound,
+        player_and_game(initiator, game),
+    )
+}
+
+pub fn auto_play_next_round(program_id: &Pubkey, initiator: &Pubkey, game: &Pubkey) -> Instruction {
+    rps_instruction(
+        *program_id,
+        &RPSInstruction::AutoPlayNextRound,
+        player_and_game(initiator, game),
+    )
+}
+
+// The initiator funds the bots' entry fees and collects their winnings
+pub fn add_bot_players(
+    program_id: &Pubkey,
+    initiator: &Pubkey,
+    game: &Pubkey,
+    mint: Option<&Pubkey>,
+    count: u8,
+) -> Instruction {
+    let (vault, _) = find_vault_address(game, program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*initiator, true),
+        AccountMeta::new(*game, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(token_accounts(initiator, &vault, mint));
+
+    rps_instruction(*program_id, &RPSInstruction::AddBotPlayers { count }, accounts)
+}
+
+pub fn initialize_config(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    fee_basis_points: u16,
+    treasury: Pubkey,
+    game_expiry_seconds: u64,
+) -> Instruction {
+    rps_instruction(
+        *program_id,
+        &RPSInstruction::InitializeConfig {
+            fee_basis_points,
+            treasury,
+            game_expiry_seconds,
+        },
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(find_config_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn update_config(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    new_admin: Pubkey,
+    fee_basis_points: u16,
+    treasury: Pubkey,
+    game_expiry_seconds: u64,
+) -> Instruction {
+    rps_instruction(
+        *program_id,
+        &RPSInstruction::UpdateConfig {
+            new_admin,
+            fee_basis_points,
+            treasury,
+            game_expiry_seconds,
+        },
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(find_config_address(program_id).0, false),
+        ],
+    )
+}
+
+// `authority` is either the config admin or the treasury itself
+pub fn withdraw_fees(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    game: &Pubkey,
+    treasury: &Pubkey,
+    mint: Option<&Pubkey>,
+) -> Instruction {
+    let (vault, _) = find_vault_address(game, program_id);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(find_config_address(program_id).0, false),
+        AccountMeta::new(*game, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(*treasury, false),
+    ];
+    accounts.extend(token_accounts(treasury, &vault, mint));
+
+    rps_instruction(*program_id, &RPSInstruction::WithdrawFees, accounts)
+}
+
+pub fn claim_refund(program_id: &Pubkey, player: &Pubkey, game: &Pubkey, mint: Option<&Pubkey>) -> Instruction {
+    let (vault, _) = find_vault_address(game, program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*player, true),
+        AccountMeta::new(*game, false),
+        AccountMeta::new(vault, false),
+    ];
+    accounts.extend(token_accounts(player, &vault, mint));
+
+    rps_instruction(*program_id, &RPSInstruction::ClaimRefund, accounts)
+}
+
+// Anyone may close a settled game; `host` and `treasury` must match the game and config
+pub fn close_game(
+    program_id: &Pubkey,
+    caller: &Pubkey,
+    game: &Pubkey,
+    host: &Pubkey,
+    treasury: &Pubkey,
+    mint: Option<&Pubkey>,
+) -> Instruction {
+    let (vault, _) = find_vault_address(game, program_id);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*caller, true),
+        AccountMeta::new_readonly(find_config_address(program_id).0, false),
+        AccountMeta::new(*game, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(*host, false),
+        AccountMeta::new(*treasury, false),
+    ];
+    accounts.extend(token_accounts(treasury, &vault, mint));
+
+    rps_instruction(*program_id, &RPSInstruction::CloseGame, accounts)
+}
+
+// Create a SOL tournament; `tournament` is a fresh keypair signing alongside the organizer
+#[allow(clippy::too_many_arguments)]
+pub fn create_tournament(
+    program_id: &Pubkey,
+    organizer: &Pubkey,
+    tournament: &Pubkey,
+    max_entrants: u8,
+    buy_in: u64,
+    match_rounds: u8,
+    commit_timeout_seconds: u64,
+    reveal_timeout_seconds: u64,
+    place_bps: Vec<u16>,
+    registration_timeout_seconds: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*organizer, true),
+        AccountMeta::new(*tournament, true),
+        AccountMeta::new(find_tournament_vault_address(tournament, program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(find_config_address(program_id).0, false),
+    ];
+
+    let instruction = RPSInstruction::CreateTournament {
+        max_entrants,
+        buy_in,
+        match_rounds,
+        commit_timeout_seconds,
+        reveal_timeout_seconds,
+        place_bps,
+        registration_timeout_seconds,
+    };
+    rps_instruction(*program_id, &instruction, accounts)
+}
+
+pub fn register_for_tournament(program_id: &Pubkey, entrant: &Pubkey, tournament: &Pubkey) -> Instruction {
+    let mut accounts = player_and_game(entrant, tournament);
+    accounts.push(AccountMeta::new(find_tournament_vault_address(tournament, program_id).0, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+
+    rps_instruction(*program_id, &RPSInstruction::RegisterForTournament, accounts)
+}
+
+// Open match `index` of bracket round `round`; these must be the tournament's next match
+pub fn open_tournament_match(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    tournament: &Pubkey,
+    round: u8,
+    index: u8,
+) -> Instruction {
+    let mut accounts = player_and_game(payer, tournament);
+    accounts.push(AccountMeta::new(find_match_address(tournament, round, index, program_id).0, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+
+    rps_instruction(*program_id, &RPSInstruction::OpenTournamentMatch, accounts)
+}
+
+// Record a finished match; `match_host` is whoever opened it and receives its rent
+pub fn advance_tournament(
+    program_id: &Pubkey,
+    caller: &Pubkey,
+    tournament: &Pubkey,
+    match_game: &Pubkey,
+    match_host: &Pubkey,
+    treasury: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*caller, true),
+        AccountMeta::new(*tournament, false),
+        AccountMeta::new(*match_game, false),
+        AccountMeta::new(*match_host, false),
+        AccountMeta::new_readonly(find_config_address(program_id).0, false),
+        AccountMeta::new(find_tournament_vault_address(tournament, program_id).0, false),
+        AccountMeta::new(*treasury, false),
+    ];
+
+    rps_instruction(*program_id, &RPSInstruction::AdvanceTournament, accounts)
+}
+
+// The organizer may cancel at any time during registration, anyone else once it has timed out
+pub fn cancel_tournament(program_id: &Pubkey, caller: &Pubkey, tournament: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*caller, true),
+        AccountMeta::new(*tournament, false),
+    ];
+
+    rps_instruction(*program_id, &RPSInstruction::CancelTournament, accounts)
+}
+
+pub fn claim_tournament_payout(program_id: &Pubkey, entrant: &Pubkey, tournament: &Pubkey) -> Instruction {
+    let mut accounts = player_and_game(entrant, tournament);
+    accounts.push(AccountMeta::new(find_tournament_vault_address(tournament, program_id).0, false));
+
+    rps_instruction(*program_id, &RPSInstruction::ClaimTournamentPayout, accounts)
+}
+
+pub fn create_player_profile(program_id: &Pubkey, wallet: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*wallet, true),
+        AccountMeta::new(find_profile_address(wallet, program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    rps_instruction(*program_id, &RPSInstruction::CreatePlayerProfile, accounts)
+}
+
+// Append the profiles of `wallets` to a RevealChoice or ResolveTimeout instruction
+// Wallets without a profile are passed the same way, by their profile address
+pub fn with_profiles(mut instruction: Instruction, wallets: &[Pubkey]) -> Instruction {
+    for wallet in wallets {
+        let (profile, _) = find_profile_address(wallet, &instruction.program_id);
+        instruction.accounts.push(AccountMeta::new(profile, false));
+    }
+    instruction
+}
+
+pub fn initialize_lobby(program_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(find_lobby_address(program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    rps_instruction(*program_id, &RPSInstruction::InitializeLobby, accounts)
+}
+
+pub fn prune_lobby(program_id: &Pubkey, games: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(find_lobby_address(program_id).0, false)];
+    accounts.extend(games.iter().map(|game| AccountMeta::new_readonly(*game, false)));
+
+    rps_instruction(*program_id, &RPSInstruction::PruneLobby, accounts)
+}
+
+// Append the open-lobby registry to an instruction that lists or delists games.
+// It must be the last account, so add it after any profiles.
+pub fn with_lobby(mut instruction: Instruction) -> Instruction {
+    let (lobby, _) = find_lobby_address(&instruction.program_id);
+    instruction.accounts.push(AccountMeta::new(lobby, false));
+    instruction
+}
+
+fn player_and_game(player: &Pubkey, game: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*player, true),
+        AccountMeta::new(*game, false),
+    ]
+}
+
+fn with_slot_hashes(signer: &Pubkey, game: &Pubkey) -> Vec<AccountMeta> {
+    let mut accounts = player_and_game(signer, game);
+    accounts.push(AccountMeta::new_readonly(slot_hashes::id(), false));
+    accounts
+}
+
+// Wrappers for driving games from other programs. Accounts are passed in the
+// order documented on `RPSInstruction`; the leading signers (the first
+// account, plus the game account for `initialize_game` and the tournament
+// account for `create_tournament`) may be PDAs of the calling program signed
+// with `signer_seeds`. `prune_lobby` needs no signer.
+#[cfg(feature = "cpi")]
+pub mod cpi {
+    use borsh::BorshSerialize;
+    use solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+        pubkey::Pubkey,
+    };
+
+    use super::GameSettings;
+    use crate::{Choice, RPSInstruction};
+
+    fn invoke_rps<'a>(
+        program: &AccountInfo<'a>,
+        instruction: &RPSInstruction,
+        accounts: &[AccountInfo<'a>],
+        signer_count: usize,
+        signer_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let metas = accounts
+            .iter()
+            .enumerate()
+            .map(|(i, account)| AccountMeta {
+                pubkey: *account.key,
+                is_signer: i < signer_count || account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect();
+
+        let instruction = Instruction {
+            program_id: *program.key,
+            accounts: metas,
+            data: instruction.try_to_vec()?,
+        };
+
+        let mut account_infos = accounts.to_vec();
+        account_infos.push(program.clone());
+        invoke_signed(&instruction, &account_infos, signer_seeds)
+    }
+
+    // `currency_mode` is 0 for SOL and 1 for RPSToken, matching the accounts passed
+    pub fn initialize_game<'a>(
+        program: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        signer_seeds: &[&[&[u8]]],
+        currency_mode: u8,
+        settings: GameSettings,
+    ) -> ProgramResult {
+        invoke_rps(program, &settings.into_instruction(currency_mode), accounts, 2, signer_seeds)
+    }
+
+    pub fn join_game<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::JoinGame, accounts, 1, signer_seeds)
+    }
+
+    // `accounts` include the join code key, which must have signed the outer transaction
+    pub fn join_game_with_code<'a>(
+        program: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        signer_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::JoinGameWithCode, accounts, 1, signer_seeds)
+    }
+
+    pub fn reveal_count_seed<'a>(
+        program: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        signer_seeds: &[&[&[u8]]],
+        seed: [u8; 32],
+    ) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::RevealCountSeed { seed }, accounts, 1, signer_seeds)
+    }
+
+    pub fn commit_choice<'a>(
+        program: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        signer_seeds: &[&[&[u8]]],
+        committed_choice: [u8; 32],
+    ) -> ProgramResult {
+        let instruction = RPSInstruction::CommitChoice { committed_choice };
+        invoke_rps(program, &instruction, accounts, 1, signer_seeds)
+    }
+
+    pub fn reveal_choice<'a>(
+        program: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        signer_seeds: &[&[&[u8]]],
+        choice: Choice,
+        salt: [u8; 32],
+    ) -> ProgramResult {
+        let instruction = RPSInstruction::RevealChoice { choice, salt };
+        invoke_rps(program, &instruction, accounts, 1, signer_seeds)
+    }
+
+    pub fn resolve_timeout<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::ResolveTimeout, accounts, 1, signer_seeds)
+    }
+
+    pub fn claim_winnings<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::ClaimWinnings, accounts, 1, signer_seeds)
+    }
+
+    pub fn rejoin_game<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::RejoinGame, accounts, 1, signer_seeds)
+    }
+
+    pub fn start_new_game_round<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::StartNewGameRound, accounts, 1, signer_seeds)
+    }
+
+    pub fn auto_play_next_round<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::AutoPlayNextRound, accounts, 1, signer_seeds)
+    }
+
+    pub fn add_bot_players<'a>(
+        program: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        signer_seeds: &[&[&[u8]]],
+        count: u8,
+    ) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::AddBotPlayers { count }, accounts, 1, signer_seeds)
+    }
+
+    pub fn initialize_config<'a>(
+        program: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        signer_seeds: &[&[&[u8]]],
+        fee_basis_points: u16,
+        treasury: Pubkey,
+        game_expiry_seconds: u64,
+    ) -> ProgramResult {
+        let instruction = RPSInstruction::InitializeConfig { fee_basis_points, treasury, game_expiry_seconds };
+        invoke_rps(program, &instruction, accounts, 1, signer_seeds)
+    }
+
+    pub fn update_config<'a>(
+        program: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        signer_seeds: &[&[&[u8]]],
+        new_admin: Pubkey,
+        fee_basis_points: u16,
+        treasury: Pubkey,
+        game_expiry_seconds: u64,
+    ) -> ProgramResult {
+        let instruction = RPSInstruction::UpdateConfig { new_admin, fee_basis_points, treasury, game_expiry_seconds };
+        invoke_rps(program, &instruction, accounts, 1, signer_seeds)
+    }
+
+    pub fn withdraw_fees<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::WithdrawFees, accounts, 1, signer_seeds)
+    }
+
+    pub fn claim_refund<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::ClaimRefund, accounts, 1, signer_seeds)
+    }
+
+    pub fn close_game<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::CloseGame, accounts, 1, signer_seeds)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_tournament<'a>(
+        program: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+        signer_seeds: &[&[&[u8]]],
+        max_entrants: u8,
+        buy_in: u64,
+        match_rounds: u8,
+        commit_timeout_seconds: u64,
+        reveal_timeout_seconds: u64,
+        place_bps: Vec<u16>,
+        registration_timeout_seconds: u64,
+    ) -> ProgramResult {
+        let instruction = RPSInstruction::CreateTournament {
+            max_entrants,
+            buy_in,
+            match_rounds,
+            commit_timeout_seconds,
+            reveal_timeout_seconds,
+            place_bps,
+            registration_timeout_seconds,
+        };
+        invoke_rps(program, &instruction, accounts, 2, signer_seeds)
+    }
+
+    pub fn register_for_tournament<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::RegisterForTournament, accounts, 1, signer_seeds)
+    }
+
+    pub fn open_tournament_match<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::OpenTournamentMatch, accounts, 1, signer_seeds)
+    }
+
+    pub fn advance_tournament<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::AdvanceTournament, accounts, 1, signer_seeds)
+    }
+
+    pub fn cancel_tournament<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::CancelTournament, accounts, 1, signer_seeds)
+    }
+
+    pub fn claim_tournament_payout<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::ClaimTournamentPayout, accounts, 1, signer_seeds)
+    }
+
+    pub fn create_player_profile<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::CreatePlayerProfile, accounts, 1, signer_seeds)
+    }
+
+    pub fn initialize_lobby<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>], signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::InitializeLobby, accounts, 1, signer_seeds)
+    }
+
+    // Pruning is permissionless, so nothing is signed on the caller's behalf
+    pub fn prune_lobby<'a>(program: &AccountInfo<'a>, accounts: &[AccountInfo<'a>]) -> ProgramResult {
+        invoke_rps(program, &RPSInstruction::PruneLobby, accounts, 0, &[])
+    }
+}
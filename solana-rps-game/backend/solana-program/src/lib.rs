@@ -1,58 +1,150 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    entrypoint,
     entrypoint::ProgramResult,
     msg,
-    program_error::ProgramError,
+    program_error::{PrintProgramError, ProgramError},
     pubkey::Pubkey,
     system_instruction,
     program::{invoke, invoke_signed},
+    program_pack::Pack,
     sysvar::{rent::Rent, Sysvar},
     clock::Clock,
 };
-use std::collections::HashMap;
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+
+pub mod access;
+pub mod commitment;
+pub mod config;
+pub mod error;
+pub mod events;
+pub mod instruction;
+pub mod lobby;
+pub mod payouts;
+pub mod profile;
+pub mod randomness;
+pub mod rating;
+pub mod rules;
+pub mod scoring;
+pub mod tournament;
+
+use crate::{
+    access::GameAccess,
+    commitment::{choice_byte, compute_commitment},
+    config::{calculate_fee, ProgramConfig, MIN_GAME_EXPIRY_SECONDS},
+    error::RpsError,
+    events::GameEvent,
+    lobby::split_lobby,
+    payouts::PayoutTable,
+    profile::{PlayerProfile, Profiles},
+    randomness::{count_seed_hash, latest_slot_hash, mix_entropy, GameRng},
+    rules::RuleSet,
+    scoring::ScoringModel,
+};
+
+// Seed prefix of the per-game vault PDA that escrows all entry fees
+pub const VAULT_SEED: &[u8] = b"vault";
+
+// Seed prefix of bot identities: PDAs of the game and the bot's seat index
+pub const BOT_SEED: &[u8] = b"bot";
+
+// Bytes kept at the end of every game account for fields added later.
+// `Game::save` zeroes everything past the serialized game, so a field appended
+// to the layout reads as zero from accounts saved before it existed; new fields
+// must be appended and treat zero as their default. Changes that cannot be made
+// that way bump GAME_LAYOUT_VERSION.
+pub const GAME_RESERVED_SPACE: usize = 128;
+
+// Version of the `Game` layout, stored right after the account type and ahead
+// of every variable-length field
+pub const GAME_LAYOUT_VERSION: u8 = 1;
 
 // Define the game state
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
-pub enum Choice {
-    None,
-    Rock,
-    Paper,
-    Scissors,
+// A choice is a 1-based index into the game's rule set; 0 means nothing was played.
+// Its encoding is a single byte, the same as the former three-choice enum.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Choice(u8);
+
+impl Choice {
+    pub const NONE: Choice = Choice(0);
+    pub const ROCK: Choice = Choice(1);
+    pub const PAPER: Choice = Choice(2);
+    pub const SCISSORS: Choice = Choice(3);
+    pub const LIZARD: Choice = Choice(4);
+    pub const SPOCK: Choice = Choice(5);
+
+    // Checked against the game's rule set when revealed
+    pub const fn new(index: u8) -> Self {
+        Choice(index)
+    }
+
+    pub const fn index(&self) -> u8 {
+        self.0
+    }
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub enum GameMode {
     Manual,
     Automated,
+    BattleRoyale, // Lowest round scorers are knocked out until `survivors` remain
+    Duel,         // Two players, first to a majority of `total_rounds` round wins
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub enum CurrencyMode {
     SOL,
     RPSToken,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct Player {
     pub pubkey: Pubkey,
     pub choice: Choice,
-    pub committed_choice: [u8; 32], // Hash of choice + salt
+    pub committed_choice: [u8; 32], // Hash bound to game, round, player, choice and salt
     pub revealed: bool,
     pub score: u8,
+    pub claimed: bool,              // Whether this player already withdrew a payout or refund
+    pub payout: u64,                // Winnings frozen at settlement and not yet claimed
+    pub is_bot: bool,               // Bots never sign; their moves are drawn on-chain
+    pub funder: Pubkey,             // Wallet that paid a bot's entry fee and collects its winnings
+    pub eliminated: bool,           // Knocked out or timed out of the commit phase; skipped until the next game
+    pub rounds_won: u8,             // Rounds of the current game in which this player scored
+    pub wagered: u64,               // Entry fees paid towards the current game
+    pub winnings: u64,              // Share of the pot frozen at the last settlement
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub enum GameState {
     WaitingForPlayers,
     CommitPhase,
     RevealPhase,
     Finished,
+    Cancelled, // Lobby timed out before filling up; entry fees are refundable
+}
+
+// Leading byte of game and tournament accounts, so that neither can be loaded
+// as the other. Closed accounts are zeroed and read as Uninitialized.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum AccountType {
+    Uninitialized,
+    Game,
+    Tournament,
+}
+
+impl AccountType {
+    // Whether `data` starts with this account type's tag
+    pub fn tags(self, data: &[u8]) -> bool {
+        data.first() == Some(&(self as u8))
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub struct Game {
+    pub account_type: AccountType,
+    pub layout_version: u8, // See GAME_LAYOUT_VERSION
     pub host: Pubkey,
     pub players: Vec<Player>,
     pub min_players: u8,
@@ -64,19 +156,44 @@ pub struct Game {
     pub game_pot: u64,
     pub required_timeout: u64,
     pub last_action_timestamp: u64,
-    pub player_count: u8,        // Actual number of players (randomized between 3-4)
+    pub player_count: u8,        // Actual number of players (drawn between min and max players)
     pub losers_can_rejoin: bool, // Indicates if losers can rejoin for another game
-    pub game_mode: GameMode,     // Manual or Automated
+    pub game_mode: GameMode,     // Manual, Automated, BattleRoyale or Duel
     pub auto_round_delay: u64,   // Time between automated rounds in seconds
     pub max_auto_rounds: u64,    // Maximum number of automated rounds
     pub current_auto_round: u64,  // Current auto round counter
     pub currency_mode: CurrencyMode, // SOL or RPSToken
+    pub vault_bump: u8,              // Bump seed of the vault PDA holding the pot
+    pub token_mint: Pubkey,          // Mint of the entry fee token (default for SOL games)
+    pub fee_basis_points: u16,       // Protocol fee snapshotted from the config at creation
+    pub fees_owed: u64,              // Fees deducted from the pot, awaiting withdrawal
+    pub commit_timeout: u64,         // Length of each commit phase in seconds
+    pub reveal_timeout: u64,         // Length of each reveal phase in seconds
+    pub commit_deadline: u64,        // Fixed when the commit phase begins
+    pub reveal_deadline: u64,        // Fixed when the reveal phase begins
+    pub entropy: [u8; 32],           // Pool feeding every random draw, see randomness.rs
+    pub tournament: Pubkey,          // Tournament this game is a bracket match of (default otherwise)
+    pub survivors: u8,               // Players left standing when a battle royale ends
+    pub opponent: Pubkey,            // Only wallet allowed to join a duel (default for open duels)
+    pub rule_set: RuleSet,           // Which choices exist and which beats which
+    pub scoring_model: ScoringModel, // How a round's choices turn into points
+    pub payout_table: PayoutTable,   // How the pot is split between players at settlement
+    pub min_rating: u16,             // Lowest profile rating allowed to join
+    pub max_rating: u16,             // Highest profile rating allowed to join (0 for no upper bound)
+    pub access: GameAccess,          // Who may join; private games take invitees or a join code
+    pub count_seed_hash: [u8; 32],   // Host's player count seed commitment; zero once revealed or if fixed
+    pub game_expiry_seconds: u64,    // Config's game expiry snapshotted at creation; zero for older games
+    pub count_seed_deadline: u64,    // Host must reveal the count seed by then; zero until the minimum is seated
 }
 
 // Define instruction types
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum RPSInstruction {
     // Initialize a new game
+    // Accounts: [signer] initializer, [signer, writable] game, [writable] vault, config, system program
+    // RPSToken games also pass: mint, [writable] initializer token account,
+    // [writable] vault token account (ATA of the vault), token program, associated token program
+    // The open-lobby registry may be passed as the very last account, see lobby.rs
     InitializeGame {
         min_players: u8,
         max_players: u8,
@@ -84,49 +201,174 @@ pub enum RPSInstruction {
         entry_fee: u64,
         timeout_seconds: u64,
         losers_can_rejoin: bool,
-        game_mode: u8,          // 0 = Manual, 1 = Automated
+        game_mode: u8,          // 0 = Manual, 1 = Automated, 2 = BattleRoyale, 3 = Duel
         currency_mode: u8,      // 0 = SOL, 1 = RPSToken
         auto_round_delay: u64,  // Only used if game_mode = Automated
         max_auto_rounds: u64,   // Only used if game_mode = Automated
+        commit_timeout_seconds: u64, // Length of each commit phase
+        reveal_timeout_seconds: u64, // Length of each reveal phase
+        survivors: u8,          // Only used if game_mode = BattleRoyale
+        opponent: Pubkey,       // Only used if game_mode = Duel; default leaves the duel open
+        rule_set: RuleSet,      // Must be a balanced dominance tournament, see rules.rs
+        scoring_model: ScoringModel,
+        payout_table: PayoutTable, // TopTwo shares must sum to 10000 basis points
+        min_rating: u16,        // Both zero for an unrated lobby
+        max_rating: u16,        // Zero for no upper bound
+        access: GameAccess,     // Private games cannot be listed in the open lobby
+        count_seed_hash: [u8; 32], // See randomness::count_seed_hash; zero when min_players == max_players
     },
 
     // Join an existing game
+    // Accounts: [signer] player, [writable] game, [writable] vault, system program
+    // Games with a rating band also pass the player's profile right after the system program
+    // The open-lobby registry may be passed as the very last account, see lobby.rs
+    // RPSToken games also pass: [writable] player token account, [writable] vault token account, token program
     JoinGame,
 
     // Submit a hashed choice (commit phase)
+    // Accounts: [signer] player, [writable] game, SlotHashes sysvar
     CommitChoice {
-        committed_choice: [u8; 32], // Hash of choice + salt
+        committed_choice: [u8; 32], // See commitment::compute_commitment
     },
 
-    // Reveal your choice
+    // Reveal your choice; the last human reveal also plays the bots' moves
+    // Accounts: [signer] player, [writable] game
+    // Followed by the [writable] profile of every human player when this settles the game, see profile.rs
+    // The open-lobby registry may be passed as the very last account, see lobby.rs
     RevealChoice {
         choice: Choice,
         salt: [u8; 32],
     },
 
     // Force resolve the game if timeout occurred
+    // Accounts: [signer] caller, [writable] game, SlotHashes sysvar
+    // Followed by the [writable] profile of every human player when this settles the game, see profile.rs
+    // The open-lobby registry may be passed as the very last account, see lobby.rs
     ResolveTimeout,
 
-    // Claim winnings after game finishes
+    // Claim winnings after game finishes, including those of bots the signer funded
+    // Accounts: [signer] winner, [writable] game, [writable] vault
+    // RPSToken games also pass: [writable] winner token account, [writable] vault token account, token program
     ClaimWinnings,
 
     // Rejoin game as a loser (if enabled)
+    // Accounts: [signer] player, [writable] game, [writable] vault, system program
+    // RPSToken games also pass: [writable] player token account, [writable] vault token account, token program
     RejoinGame,
 
     // Start a new game round with same players
+    // Accounts: [signer] initiator, [writable] game
     StartNewGameRound,
 
     // For auto-play, trigger the next round
+    // Accounts: [signer] initiator, [writable] game
     AutoPlayNextRound,
 
-    // For auto-play, add bot players
+    // For auto-play, add bot players; the initiator pays their entry fees and collects their winnings
+    // Accounts: [signer, writable] initiator, [writable] game, [writable] vault, system program
+    // RPSToken games also pass: [writable] initiator token account, [writable] vault token account, token program
+    // The open-lobby registry may be passed as the very last account, see lobby.rs
     AddBotPlayers {
         count: u8,
     },
+
+    // Create the global program config; the signer becomes the admin
+    // Accounts: [signer] admin, [writable] config, system program
+    InitializeConfig {
+        fee_basis_points: u16,
+        treasury: Pubkey,
+        game_expiry_seconds: u64,
+    },
+
+    // Update the global program config
+    // Accounts: [signer] admin, [writable] config
+    UpdateConfig {
+        new_admin: Pubkey,
+        fee_basis_points: u16,
+        treasury: Pubkey,
+        game_expiry_seconds: u64,
+    },
+
+    // Send a finished game's fees to the treasury
+    // Accounts: [signer] admin or treasury, config, [writable] game, [writable] vault, [writable] treasury
+    // RPSToken games also pass: [writable] treasury token account, [writable] vault token account, token program
+    WithdrawFees,
+
+    // Reclaim your entry fee, and those of bots you funded, from a cancelled game
+    // Accounts: [signer] player, [writable] game, [writable] vault
+    // RPSToken games also pass: [writable] player token account, [writable] vault token account, token program
+    ClaimRefund,
+
+    // Close a settled game: rent goes back to the host, leftover funds to the treasury
+    // Accounts: [signer] caller, config, [writable] game, [writable] vault, [writable] host, [writable] treasury
+    // RPSToken games also pass: [writable] treasury token account, [writable] vault token account, token program
+    CloseGame,
+
+    // Create a single-elimination tournament; registration opens immediately
+    // Accounts: [signer, writable] organizer, [signer, writable] tournament, [writable] vault, system program, config
+    CreateTournament {
+        max_entrants: u8,             // Power of two, at most 64
+        buy_in: u64,
+        match_rounds: u8,             // Rounds played in every match
+        commit_timeout_seconds: u64,
+        reveal_timeout_seconds: u64,
+        place_bps: Vec<u16>,          // Prize share per place, champion first; must sum to 10000
+        registration_timeout_seconds: u64, // After this long anyone may cancel an unfilled bracket
+    },
+
+    // Pay the buy-in and take the next bracket slot; the bracket starts when full
+    // Accounts: [signer, writable] entrant, [writable] tournament, [writable] vault, system program
+    RegisterForTournament,
+
+    // Create the next match of the current bracket round; the payer funds its rent
+    // Accounts: [signer, writable] payer, [writable] tournament, [writable] match (see tournament::find_match_address), system program
+    OpenTournamentMatch,
+
+    // Record a finished match, close it and advance the bracket; recording the
+    // final sends the protocol fee to the treasury
+    // Accounts: [signer] caller, [writable] tournament, [writable] match, [writable] match host, config,
+    // [writable] vault, [writable] treasury
+    AdvanceTournament,
+
+    // Cancel a tournament that has not started so entrants can reclaim their buy-ins.
+    // The organizer may cancel at any time; anyone may once registration has timed out.
+    // Accounts: [signer] caller, [writable] tournament
+    CancelTournament,
+
+    // Claim a tournament prize, or a buy-in from a cancelled tournament
+    // Accounts: [signer, writable] entrant, [writable] tournament, [writable] vault
+    ClaimTournamentPayout,
+
+    // Create the lifetime stats profile of the signing wallet
+    // Accounts: [signer, writable] wallet, [writable] profile (see profile::find_profile_address), system program
+    CreatePlayerProfile,
+
+    // Create the open-lobby registry
+    // Accounts: [signer, writable] payer, [writable] lobby (see lobby::find_lobby_address), system program
+    InitializeLobby,
+
+    // Remove listed games that no longer wait for players or whose lobby timed out
+    // Accounts: [writable] lobby, then any number of listed game accounts
+    PruneLobby,
+
+    // Join a private game, proving knowledge of its join code without revealing it
+    // Accounts: as JoinGame, with the [signer] join code key (see access::join_code_seed)
+    // right after the system program
+    JoinGameWithCode,
+
+    // Reveal the seed the host committed to, drawing the player count of a lobby
+    // with a range of sizes once the minimum number of players is seated. The host
+    // has one reveal timeout to do so; after that the lobby can only be cancelled.
+    // Accounts: [signer] host, [writable] game
+    // The open-lobby registry may be passed as the very last account, see lobby.rs
+    RevealCountSeed {
+        seed: [u8; 32],
+    },
 }
 
-// Program entrypoint
-entrypoint!(process_instruction);
+// Program entrypoint; left out when the crate is used as a library or for CPI
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
 
 // Process instruction logic
 pub fn process_instruction(
@@ -136,7 +378,7 @@ pub fn process_instruction(
 ) -> ProgramResult {
     let instruction = RPSInstruction::try_from_slice(instruction_data)?;
 
-    match instruction {
+    let result = match instruction {
         RPSInstruction::InitializeGame {
             min_players,
             max_players,
@@ -147,7 +389,18 @@ pub fn process_instruction(
             game_mode,
             currency_mode,
             auto_round_delay,
-            max_auto_rounds
+            max_auto_rounds,
+            commit_timeout_seconds,
+            reveal_timeout_seconds,
+            survivors,
+            opponent,
+            rule_set,
+            scoring_model,
+            payout_table,
+            min_rating,
+            max_rating,
+            access,
+            count_seed_hash,
         } => {
             process_initialize_game(
                 program_id,
@@ -161,11 +414,22 @@ pub fn process_instruction(
                 game_mode,
                 currency_mode,
                 auto_round_delay,
-                max_auto_rounds
+                max_auto_rounds,
+                commit_timeout_seconds,
+                reveal_timeout_seconds,
+                survivors,
+                opponent,
+                rule_set,
+                scoring_model,
+                payout_table,
+                min_rating,
+                max_rating,
+                access,
+                count_seed_hash,
             )
         },
         RPSInstruction::JoinGame => {
-            process_join_game(program_id, accounts)
+            process_join_game(program_id, accounts, false)
         },
         RPSInstruction::CommitChoice { committed_choice } => {
             process_commit_choice(program_id, accounts, committed_choice)
@@ -191,10 +455,97 @@ pub fn process_instruction(
         RPSInstruction::AddBotPlayers { count } => {
             process_add_bot_players(program_id, accounts, count)
         },
+        RPSInstruction::InitializeConfig { fee_basis_points, treasury, game_expiry_seconds } => {
+            config::process_initialize_config(
+                program_id,
+                accounts,
+                fee_basis_points,
+                treasury,
+                game_expiry_seconds,
+            )
+        },
+        RPSInstruction::UpdateConfig { new_admin, fee_basis_points, treasury, game_expiry_seconds } => {
+            config::process_update_config(
+                program_id,
+                accounts,
+                new_admin,
+                fee_basis_points,
+                treasury,
+                game_expiry_seconds,
+            )
+        },
+        RPSInstruction::WithdrawFees => {
+            config::process_withdraw_fees(program_id, accounts)
+        },
+        RPSInstruction::ClaimRefund => {
+            process_claim_refund(program_id, accounts)
+        },
+        RPSInstruction::CloseGame => {
+            process_close_game(program_id, accounts)
+        },
+        RPSInstruction::CreateTournament {
+            max_entrants,
+            buy_in,
+            match_rounds,
+            commit_timeout_seconds,
+            reveal_timeout_seconds,
+            place_bps,
+            registration_timeout_seconds,
+        } => {
+            tournament::process_create_tournament(
+                program_id,
+                accounts,
+                max_entrants,
+                buy_in,
+                match_rounds,
+                commit_timeout_seconds,
+                reveal_timeout_seconds,
+                place_bps,
+                registration_timeout_seconds,
+            )
+        },
+        RPSInstruction::RegisterForTournament => {
+            tournament::process_register_for_tournament(program_id, accounts)
+        },
+        RPSInstruction::OpenTournamentMatch => {
+            tournament::process_open_tournament_match(program_id, accounts)
+        },
+        RPSInstruction::AdvanceTournament => {
+            tournament::process_advance_tournament(program_id, accounts)
+        },
+        RPSInstruction::CancelTournament => {
+            tournament::process_cancel_tournament(program_id, accounts)
+        },
+        RPSInstruction::ClaimTournamentPayout => {
+            tournament::process_claim_tournament_payout(program_id, accounts)
+        },
+        RPSInstruction::CreatePlayerProfile => {
+            profile::process_create_player_profile(program_id, accounts)
+        },
+        RPSInstruction::InitializeLobby => {
+            lobby::process_initialize_lobby(program_id, accounts)
+        },
+        RPSInstruction::PruneLobby => {
+            lobby::process_prune_lobby(program_id, accounts)
+        },
+        RPSInstruction::JoinGameWithCode => {
+            process_join_game(program_id, accounts, true)
+        },
+        RPSInstruction::RevealCountSeed { seed } => {
+            process_reveal_count_seed(program_id, accounts, seed)
+        },
+    };
+
+    if let Err(error) = &result {
+        // Log the decoded message for our custom errors
+        error.print::<RpsError>();
     }
+
+    result
 }
 
 // Implementation for initializing a new game
+#[allow(clippy::too_many_arguments)]
 fn process_initialize_game(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -208,11 +559,24 @@ fn process_initialize_game(
     currency_mode: u8,
     auto_round_delay: u64,
     max_auto_rounds: u64,
+    commit_timeout_seconds: u64,
+    reveal_timeout_seconds: u64,
+    survivors: u8,
+    opponent: Pubkey,
+    rule_set: RuleSet,
+    scoring_model: ScoringModel,
+    payout_table: PayoutTable,
+    min_rating: u16,
+    max_rating: u16,
+    access: GameAccess,
+    count_seed_hash: [u8; 32],
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
     let initializer = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
 
     // Ensure the initializer signed the transaction
@@ -220,67 +584,190 @@ fn process_initialize_game(
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    // Validate parameters - ensure only 3 or 4 players
-    if min_players != 3 || (max_players != 3 && max_players != 4) || min_players > max_players {
-        return Err(ProgramError::InvalidArgument);
-    }
-
-    if total_rounds == 0 {
-        return Err(ProgramError::InvalidArgument);
-    }
+    // The protocol fee is fixed for the lifetime of the game
+    let config = ProgramConfig::load(program_id, config_account)?;
 
     // Parse game mode
     let game_mode = match game_mode {
         0 => GameMode::Manual,
         1 => GameMode::Automated,
-        _ => return Err(ProgramError::InvalidArgument),
+        2 => GameMode::BattleRoyale,
+        3 => GameMode::Duel,
+        _ => return Err(RpsError::InvalidGameMode.into()),
+    };
+
+    // Validate parameters - duels seat exactly two players, other games 3 or 4
+    if matches!(game_mode, GameMode::Duel) {
+        if min_players != 2 || max_players != 2 {
+            return Err(RpsError::InvalidPlayerCount.into());
+        }
+    } else if min_players != 3 || (max_players != 3 && max_players != 4) || min_players > max_players {
+        return Err(RpsError::InvalidPlayerCount.into());
+    }
+
+    // A duel is best-of-N, so N must be odd for a majority to exist
+    if total_rounds == 0 || (matches!(game_mode, GameMode::Duel) && total_rounds % 2 == 0) {
+        return Err(RpsError::InvalidTotalRounds.into());
+    }
+
+    // Both phases need a non-zero window, otherwise they could be timed out instantly
+    if commit_timeout_seconds == 0 || reveal_timeout_seconds == 0 {
+        return Err(RpsError::InvalidPhaseTimeout.into());
+    }
+
+    rule_set.validate()?;
+    payout_table.validate()?;
+
+    if max_rating != 0 && min_rating > max_rating {
+        return Err(RpsError::InvalidRatingBand.into());
+    }
+
+    access.validate()?;
+
+    // Only a duel can name its opponent, and the host cannot name themselves
+    if opponent != Pubkey::default()
+        && (!matches!(game_mode, GameMode::Duel) || opponent == *initializer.key)
+    {
+        return Err(RpsError::InvalidOpponent.into());
+    }
+
+    // Duels need a model that always scores a won round; with two players, for
+    // one, the most common choice is never unique and MajorityLoses awards nothing
+    if matches!(game_mode, GameMode::Duel)
+        && !matches!(scoring_model, ScoringModel::Pairwise | ScoringModel::RoundWinners)
+    {
+        return Err(RpsError::InvalidDuelScoring.into());
+    }
+
+    // A battle royale must knock out at least one player
+    let survivors = match game_mode {
+        GameMode::BattleRoyale if survivors == 0 || survivors >= min_players => {
+            return Err(RpsError::InvalidSurvivorCount.into());
+        }
+        GameMode::BattleRoyale => survivors,
+        _ => 0,
     };
 
     // Parse currency mode
     let currency_mode = match currency_mode {
         0 => CurrencyMode::SOL,
         1 => CurrencyMode::RPSToken,
-        _ => return Err(ProgramError::InvalidArgument),
+        _ => return Err(RpsError::InvalidCurrencyMode.into()),
     };
 
     // Create game account
     let rent = Rent::get()?;
-    let rent_lamports = rent.minimum_balance(Game::get_max_size(max_players));
+    let rent_lamports = rent.minimum_balance(Game::space(max_players));
 
     invoke(
         &system_instruction::create_account(
             initializer.key,
             game_account.key,
             rent_lamports,
-            Game::get_max_size(max_players) as u64,
+            Game::space(max_players) as u64,
             program_id,
         ),
         &[initializer.clone(), game_account.clone(), system_program.clone()],
     )?;
 
+    // Create the vault PDA that escrows the pot; the program owns it so payouts
+    // can debit it directly
+    let (vault_key, vault_bump) = find_vault_address(game_account.key, program_id);
+    if vault_key != *vault.key {
+        return Err(RpsError::InvalidVault.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            initializer.key,
+            vault.key,
+            rent.minimum_balance(0),
+            0,
+            program_id,
+        ),
+        &[initializer.clone(), vault.clone(), system_program.clone()],
+        &[&[VAULT_SEED, game_account.key.as_ref(), &[vault_bump]]],
+    )?;
+
+    // For token games, create the vault's associated token account and take the host's fee in tokens
+    let token_mint = match currency_mode {
+        CurrencyMode::SOL => Pubkey::default(),
+        CurrencyMode::RPSToken => {
+            let mint = next_account_info(accounts_iter)?;
+            let initializer_token_account = next_account_info(accounts_iter)?;
+            let vault_token_account = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            let associated_token_program = next_account_info(accounts_iter)?;
+
+            if *token_program.key != spl_token::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+
+            if *mint.owner != spl_token::id() {
+                return Err(RpsError::InvalidMint.into());
+            }
+
+            if *vault_token_account.key
+                != get_associated_token_address(vault.key, mint.key)
+            {
+                return Err(RpsError::InvalidTokenAccount.into());
+            }
+
+            invoke(
+                &create_associated_token_account(
+                    initializer.key,
+                    vault.key,
+                    mint.key,
+                    token_program.key,
+                ),
+                &[
+                    initializer.clone(),
+                    vault_token_account.clone(),
+                    vault.clone(),
+                    mint.clone(),
+                    system_program.clone(),
+                    token_program.clone(),
+                    associated_token_program.clone(),
+                ],
+            )?;
+
+            if entry_fee > 0 {
+                transfer_tokens_to_vault(
+                    initializer,
+                    initializer_token_account,
+                    vault_token_account,
+                    token_program,
+                    entry_fee,
+                )?;
+            }
+
+            *mint.key
+        }
+    };
+
     // Initialize host as first player
-    let mut players = Vec::new();
-    players.push(Player {
-        pubkey: *initializer.key,
-        choice: Choice::None,
-        committed_choice: [0; 32],
-        revealed: false,
-        score: 0,
-    });
+    let players = vec![Player {
+        wagered: entry_fee,
+        ..Player::new(*initializer.key)
+    }];
 
     // Initialize game state
     let clock = Clock::get()?;
 
-    // Randomly choose the actual player count (either 3 or 4)
-    let player_count = if min_players == max_players {
-        min_players
-    } else {
-        // Use the last bit of the timestamp as randomness
-        // This is not cryptographically secure but sufficient for this purpose
-        if (clock.unix_timestamp & 1) == 0 { 3 } else { 4 }
-    };
+    // Lobbies with a range of sizes open every seat; the actual player count is
+    // drawn once the minimum number of players is seated and the host reveals
+    // the seed committed to here
+    if (min_players != max_players) != (count_seed_hash != [0; 32]) {
+        return Err(RpsError::InvalidCountSeedHash.into());
+    }
+    let player_count = max_players;
+
+    let mut entropy = [0u8; 32];
+    mix_entropy(&mut entropy, &[game_account.key.as_ref(), initializer.key.as_ref()]);
 
     let game = Game {
+        account_type: AccountType::Game,
+        layout_version: GAME_LAYOUT_VERSION,
         host: *initializer.key,
         players,
         min_players,
@@ -299,24 +786,47 @@ fn process_initialize_game(
         max_auto_rounds,
         current_auto_round: 0,
         currency_mode,
+        vault_bump,
+        token_mint,
+        fee_basis_points: config.fee_basis_points,
+        fees_owed: 0,
+        commit_timeout: commit_timeout_seconds,
+        reveal_timeout: reveal_timeout_seconds,
+        commit_deadline: 0,
+        reveal_deadline: 0,
+        entropy,
+        tournament: Pubkey::default(),
+        survivors,
+        opponent,
+        rule_set,
+        scoring_model,
+        payout_table,
+        min_rating,
+        max_rating,
+        access,
+        count_seed_hash,
+        game_expiry_seconds: config.game_expiry_seconds,
+        count_seed_deadline: 0,
     };
 
     // Save game state to account
-    game.serialize(&mut *game_account.data.borrow_mut())?;
+    game.save(game_account)?;
 
-    // Transfer entry fee from initializer to game account
-    if entry_fee > 0 && matches!(currency_mode, CurrencyMode::SOL) {
-        invoke(
-            &system_instruction::transfer(
-                initializer.key,
-                game_account.key,
-                entry_fee,
-            ),
-            &[initializer.clone(), game_account.clone(), system_program.clone()],
-        )?;
+    // Transfer entry fee from initializer to the vault (token fees were taken above)
+    if entry_fee > 0 && matches!(game.currency_mode, CurrencyMode::SOL) {
+        transfer_to_vault(initializer, vault, system_program, entry_fee)?;
+    }
+
+    // List the game in the open lobby if the registry was passed
+    if let (Some(lobby_account), _) = split_lobby(program_id, accounts) {
+        if game.access.is_private() {
+            return Err(RpsError::PrivateGameListed.into());
+        }
+        lobby::list_game(program_id, lobby_account, initializer, game_account, system_program, &game)?;
     }
 
-    // If using RPS tokens, would handle token transfers here
+    GameEvent::game_created(game_account.key, &game).emit()?;
+    GameEvent::player_joined(game_account.key, &game.players[0]).emit()?;
 
     msg!("Game initialized with ID: {}", game_account.key);
     Ok(())
@@ -326,11 +836,13 @@ fn process_initialize_game(
 fn process_join_game(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    with_code: bool,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
     let player = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
 
     // Ensure the player signed the transaction
@@ -339,62 +851,76 @@ fn process_join_game(
     }
 
     // Load game state
-    let mut game = Game::try_from_slice(&game_account.data.borrow())?;
+    let mut game = Game::load(program_id, game_account)?;
+    game.check_vault(program_id, game_account.key, vault)?;
 
     // Check if game is in correct state
     if game.state != GameState::WaitingForPlayers {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(RpsError::GameNotJoinable.into());
+    }
+
+    // A duel with a named opponent is reserved for them
+    if game.opponent != Pubkey::default() && game.opponent != *player.key {
+        return Err(RpsError::NotInvitedOpponent.into());
+    }
+
+    // Private games only seat invited wallets or holders of the join code
+    let code_signer = if with_code {
+        let code_key = next_account_info(accounts_iter)?;
+        if !code_key.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Some(code_key.key)
+    } else {
+        None
+    };
+    game.access.admit(player.key, code_signer)?;
+
+    // Rated lobbies only seat players whose profile rating is within the band
+    if game.has_rating_band() {
+        let profile_account = next_account_info(accounts_iter)?;
+        let profile = PlayerProfile::load(program_id, profile_account)?;
+        if profile.wallet != *player.key {
+            return Err(RpsError::InvalidProfileAccount.into());
+        }
+
+        if !game.accepts_rating(profile.current_rating()) {
+            return Err(RpsError::RatingOutOfBand.into());
+        }
     }
 
     // Check if player already joined
     for existing_player in &game.players {
         if existing_player.pubkey == *player.key {
-            return Err(ProgramError::InvalidArgument);
+            return Err(RpsError::PlayerAlreadyJoined.into());
         }
     }
 
     // Check if game is full based on the randomized player_count
-    if game.players.len() >= game.player_count as usize {
-        return Err(ProgramError::InvalidArgument);
+    if game.open_seats() == 0 {
+        return Err(RpsError::GameFull.into());
     }
 
     // Add player to the game
-    game.players.push(Player {
-        pubkey: *player.key,
-        choice: Choice::None,
-        committed_choice: [0; 32],
-        revealed: false,
-        score: 0,
-    });
-
-    // Update game pot
-    game.game_pot += game.entry_fee;
-
-    // Update game state if required player count is reached
-    if game.players.len() >= game.player_count as usize {
-        game.state = GameState::CommitPhase;
-        msg!("Required player count reached: {}", game.player_count);
-    }
+    let clock = Clock::get()?;
+    seat_player(&mut game, Player::new(*player.key), clock.unix_timestamp as u64);
 
     // Update last action timestamp
-    let clock = Clock::get()?;
     game.last_action_timestamp = clock.unix_timestamp as u64;
 
     // Save game state
-    game.serialize(&mut *game_account.data.borrow_mut())?;
+    game.save(game_account)?;
 
     // Transfer entry fee
-    if game.entry_fee > 0 {
-        invoke(
-            &system_instruction::transfer(
-                player.key,
-                game_account.key,
-                game.entry_fee,
-            ),
-            &[player.clone(), game_account.clone(), system_program.clone()],
-        )?;
+    collect_entry_fee(&game, player, vault, system_program, accounts_iter, game.entry_fee)?;
+
+    // A game that filled up leaves the open lobby
+    if let (Some(lobby_account), _) = split_lobby(program_id, accounts) {
+        lobby::update_listing(program_id, lobby_account, game_account.key, &game)?;
     }
 
+    GameEvent::PlayerJoined { game: *game_account.key, player: *player.key, is_bot: false }.emit()?;
+
     msg!("Player joined game: {}", player.key);
 
     Ok(())
@@ -410,6 +936,7 @@ fn process_commit_choice(
 
     let player = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
+    let slot_hashes = next_account_info(accounts_iter)?;
 
     // Ensure the player signed the transaction
     if !player.is_signer {
@@ -417,17 +944,27 @@ fn process_commit_choice(
     }
 
     // Load game state
-    let mut game = Game::try_from_slice(&game_account.data.borrow())?;
+    let mut game = Game::load(program_id, game_account)?;
 
     // Check if game is in correct state
     if game.state != GameState::CommitPhase {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(RpsError::NotCommitPhase.into());
+    }
+
+    // Late commits are rejected; the phase must be closed with ResolveTimeout
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+    if current_time >= game.commit_deadline {
+        return Err(RpsError::CommitDeadlinePassed.into());
     }
 
     // Find player and update their committed choice
     let mut player_found = false;
     for game_player in &mut game.players {
         if game_player.pubkey == *player.key {
+            if game_player.eliminated {
+                return Err(RpsError::PlayerEliminated.into());
+            }
             game_player.committed_choice = committed_choice;
             player_found = true;
             break;
@@ -435,22 +972,26 @@ fn process_commit_choice(
     }
 
     if !player_found {
-        return Err(ProgramError::InvalidArgument);
+        return Err(RpsError::PlayerNotInGame.into());
     }
 
     // Check if all players have committed and transition to reveal phase if so
-    let all_committed = game.players.iter().all(|p| p.committed_choice != [0; 32]);
+    // Bots do not commit: their moves are drawn after every human has revealed
+    let all_committed = game.players
+        .iter()
+        .all(|p| p.is_bot || p.eliminated || p.committed_choice != [0; 32]);
 
     if all_committed {
-        game.state = GameState::RevealPhase;
+        game.begin_reveal_phase(current_time, &latest_slot_hash(slot_hashes)?);
     }
 
     // Update last action timestamp
-    let clock = Clock::get()?;
-    game.last_action_timestamp = clock.unix_timestamp as u64;
+    game.last_action_timestamp = current_time;
 
     // Save game state
-    game.serialize(&mut *game_account.data.borrow_mut())?;
+    game.save(game_account)?;
+
+    GameEvent::Committed { game: *game_account.key, player: *player.key, round: game.current_round }.emit()?;
 
     msg!("Player committed choice: {}", player.key);
 
@@ -475,11 +1016,25 @@ fn process_reveal_choice(
     }
 
     // Load game state
-    let mut game = Game::try_from_slice(&game_account.data.borrow())?;
+    let mut game = Game::load(program_id, game_account)?;
+    let (lobby_account, profile_accounts) = split_lobby(program_id, accounts_iter.as_slice());
+    let mut profiles = Profiles::load(program_id, &game, profile_accounts)?;
 
     // Check if game is in correct state
     if game.state != GameState::RevealPhase {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(RpsError::NotRevealPhase.into());
+    }
+
+    // Late reveals are rejected; the phase must be closed with ResolveTimeout
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+    if current_time >= game.reveal_deadline {
+        return Err(RpsError::RevealDeadlinePassed.into());
+    }
+
+    // Only choices of the game's rule set can be revealed
+    if !game.rule_set.is_valid_choice(&choice) {
+        return Err(RpsError::InvalidChoice.into());
     }
 
     // Find player's index and verify the commit matches reveal
@@ -488,64 +1043,64 @@ fn process_reveal_choice(
         if game_player.pubkey == *player.key {
             player_index = Some(i);
 
-            // Verify that the revealed choice matches the committed choice
-            let mut hash_input = [0u8; 64];
-            hash_input[0..32].copy_from_slice(&salt);
-            // Serialize choice into bytes and add to hash input
-            let choice_bytes = match choice {
-                Choice::Rock => 1u8,
-                Choice::Paper => 2u8,
-                Choice::Scissors => 3u8,
-                Choice::None => return Err(ProgramError::InvalidArgument),
-            };
-            hash_input[32] = choice_bytes;
-
-            let hash = solana_program::hash::hash(&hash_input).to_bytes();
+            // Verify that the revealed choice matches the committed choice for
+            // this game, round and player
+            let choice_byte = choice_byte(&choice).ok_or(RpsError::InvalidChoice)?;
+            let hash = compute_commitment(
+                game_account.key,
+                game.current_round,
+                player.key,
+                choice_byte,
+                &salt,
+            );
 
             if hash != game_player.committed_choice {
-                return Err(ProgramError::InvalidArgument);
+                return Err(RpsError::CommitmentMismatch.into());
             }
 
             break;
         }
     }
 
-    let player_index = player_index.ok_or(ProgramError::InvalidArgument)?;
+    let player_index = player_index.ok_or(RpsError::PlayerNotInGame)?;
+
+    // Revealed salts feed the entropy pool used by later draws
+    mix_entropy(&mut game.entropy, &[player.key.as_ref(), &salt]);
 
     // Update player's choice and revealed status
     game.players[player_index].choice = choice;
     game.players[player_index].revealed = true;
+    profiles.record_choice(player.key, &choice);
+    GameEvent::Revealed {
+        game: *game_account.key,
+        player: *player.key,
+        round: game.current_round,
+        choice,
+    }
+    .emit()?;
 
-    // Check if all players have revealed and process round if so
-    let all_revealed = game.players.iter().all(|p| p.revealed);
+    // Check if all human players have revealed and process round if so
+    let all_revealed = game.players.iter().all(|p| p.is_bot || p.eliminated || p.revealed);
 
     if all_revealed {
-        // Calculate round winners
-        process_round_results(&mut game);
+        complete_round(&mut game, game_account.key, current_time)?;
+    }
 
-        // Check if game should end
-        if game.current_round >= game.total_rounds {
-            game.state = GameState::Finished;
-        } else {
-            // Reset for next round
-            game.current_round += 1;
-            game.state = GameState::CommitPhase;
-
-            // Reset player choices for next round
-            for player in &mut game.players {
-                player.choice = Choice::None;
-                player.committed_choice = [0; 32];
-                player.revealed = false;
-            }
-        }
+    if game.state == GameState::Finished {
+        profiles.record_settlement(&mut game)?;
+        GameEvent::game_finished(game_account.key, &game).emit()?;
     }
 
     // Update last action timestamp
-    let clock = Clock::get()?;
-    game.last_action_timestamp = clock.unix_timestamp as u64;
+    game.last_action_timestamp = current_time;
 
     // Save game state
-    game.serialize(&mut *game_account.data.borrow_mut())?;
+    game.save(game_account)?;
+    profiles.save()?;
+
+    if let Some(lobby_account) = lobby_account {
+        lobby::update_listing(program_id, lobby_account, game_account.key, &game)?;
+    }
 
     msg!("Player revealed choice: {}", player.key);
 
@@ -561,6 +1116,7 @@ fn process_resolve_timeout(
 
     let caller = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
+    let slot_hashes = next_account_info(accounts_iter)?;
 
     // Ensure the caller signed the transaction
     if !caller.is_signer {
@@ -568,80 +1124,109 @@ fn process_resolve_timeout(
     }
 
     // Load game state
-    let mut game = Game::try_from_slice(&game_account.data.borrow())?;
+    let mut game = Game::load(program_id, game_account)?;
+    let (lobby_account, profile_accounts) = split_lobby(program_id, accounts_iter.as_slice());
+    let mut profiles = Profiles::load(program_id, &game, profile_accounts)?;
 
-    // Check if timeout has occurred
+    // Check if timeout has occurred. The lobby times out after a period of
+    // inactivity; the commit and reveal phases have deadlines fixed when they
+    // began, so trickled actions cannot extend them.
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp as u64;
-    let time_elapsed = current_time.saturating_sub(game.last_action_timestamp);
+    let deadline = match game.state {
+        GameState::WaitingForPlayers => game.lobby_deadline(),
+        GameState::CommitPhase => game.commit_deadline,
+        GameState::RevealPhase => game.reveal_deadline,
+        GameState::Finished => return Err(RpsError::GameAlreadyFinished.into()),
+        GameState::Cancelled => return Err(RpsError::GameCancelled.into()),
+    };
 
-    if time_elapsed < game.required_timeout {
-        return Err(ProgramError::InvalidArgument);
+    if current_time < deadline {
+        return Err(RpsError::TimeoutNotReached.into());
     }
 
     // Process timeout based on current game state
     match game.state {
         GameState::WaitingForPlayers => {
-            // Refund entry fees and end game
-            // (Simplified - would need additional accounts for refunds)
-            game.state = GameState::Finished;
+            // Cancel the lobby; every player reclaims their entry fee with ClaimRefund
+            game.state = GameState::Cancelled;
+        },
+        GameState::CommitPhase if matches!(game.game_mode, GameMode::BattleRoyale) => {
+            // Knock out players who didn't commit, unless nobody did
+            if game.players.iter().filter(|p| !p.eliminated).all(Player::missed_commit) {
+                finish_game(&mut game);
+            } else {
+                for player in game.players.iter_mut().filter(|p| p.missed_commit()) {
+                    player.eliminated = true;
+                }
+
+                if game.active_player_count() <= game.survivors as usize {
+                    finish_game(&mut game);
+                } else {
+                    game.begin_reveal_phase(current_time, &latest_slot_hash(slot_hashes)?);
+                }
+            }
+        },
+        GameState::CommitPhase if matches!(game.game_mode, GameMode::Duel) || game.tournament != Pubkey::default() => {
+            // A player who didn't commit forfeits the duel or bracket match; if neither
+            // did it is a draw. The forfeiter keeps their seat, and with it any unclaimed payout.
+            if game.players.iter().filter(|p| p.missed_commit()).count() == 1 {
+                for player in game.players.iter_mut().filter(|p| p.missed_commit()) {
+                    player.eliminated = true;
+                }
+            }
+            finish_game(&mut game);
         },
         GameState::CommitPhase => {
-            // Remove players who didn't commit and continue
-            let committed_players: Vec<Player> = game.players
+            // Sit out players who didn't commit and continue. They keep their
+            // seats, and with them any payout still owed from an earlier game.
+            let committed = game.players
                 .iter()
-                .filter(|p| p.committed_choice != [0; 32])
-                .cloned()
-                .collect();
+                .filter(|p| !p.eliminated && !p.missed_commit())
+                .count();
 
-            if committed_players.len() >= game.min_players as usize {
-                game.players = committed_players;
-                game.state = GameState::RevealPhase;
+            if committed >= game.min_players as usize {
+                for player in game.players.iter_mut().filter(|p| p.missed_commit()) {
+                    player.eliminated = true;
+                }
+                game.begin_reveal_phase(current_time, &latest_slot_hash(slot_hashes)?);
             } else {
                 // Not enough players committed, end game
-                game.state = GameState::Finished;
+                finish_game(&mut game);
             }
         },
         GameState::RevealPhase => {
             // Process round with revealed choices only
             // Players who didn't reveal get a default loss
 
-            for player in &mut game.players {
+            for player in game.players.iter_mut().filter(|p| !p.is_bot && !p.eliminated) {
                 if !player.revealed {
-                    player.choice = Choice::None;
+                    player.choice = Choice::NONE;
                     player.revealed = true;
                 }
             }
 
-            // Calculate round winners
-            process_round_results(&mut game);
-
-            // Check if game should end
-            if game.current_round >= game.total_rounds {
-                game.state = GameState::Finished;
-            } else {
-                // Reset for next round
-                game.current_round += 1;
-                game.state = GameState::CommitPhase;
-
-                // Reset player choices for next round
-                for player in &mut game.players {
-                    player.choice = Choice::None;
-                    player.committed_choice = [0; 32];
-                    player.revealed = false;
-                }
-            }
-        },
-        GameState::Finished => {
-            return Err(ProgramError::InvalidAccountData);
+            complete_round(&mut game, game_account.key, current_time)?;
         },
+        // Settled games were rejected above
+        GameState::Finished | GameState::Cancelled => {},
+    }
+
+    if game.state == GameState::Finished {
+        profiles.record_settlement(&mut game)?;
+        GameEvent::game_finished(game_account.key, &game).emit()?;
     }
 
     // Update last action timestamp
     game.last_action_timestamp = current_time;
 
     // Save game state
-    game.serialize(&mut *game_account.data.borrow_mut())?;
+    game.save(game_account)?;
+    profiles.save()?;
+
+    if let Some(lobby_account) = lobby_account {
+        lobby::update_listing(program_id, lobby_account, game_account.key, &game)?;
+    }
 
     msg!("Timeout resolved");
 
@@ -657,7 +1242,7 @@ fn process_claim_winnings(
 
     let winner = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
-    let system_program = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
 
     // Ensure the winner signed the transaction
     if !winner.is_signer {
@@ -665,64 +1250,212 @@ fn process_claim_winnings(
     }
 
     // Load game state
-    let mut game = Game::try_from_slice(&game_account.data.borrow())?;
+    let mut game = Game::load(program_id, game_account)?;
+    game.check_vault(program_id, game_account.key, vault)?;
+
+    // Payouts were frozen when the game settled, so the amount owed does not
+    // depend on the order in which winners claim. The signer collects their own
+    // seat and every bot seat they funded.
+    let mut winner_share = 0;
+    let mut already_claimed = false;
+    for player_data in game.players.iter_mut().filter(|p| p.is_owned_by(winner.key)) {
+        if player_data.payout > 0 {
+            winner_share += player_data.payout;
+            player_data.payout = 0;
+            player_data.claimed = true;
+        } else if player_data.claimed {
+            already_claimed = true;
+        }
+    }
+
+    if winner_share == 0 {
+        if already_claimed {
+            return Err(RpsError::AlreadyClaimed.into());
+        }
+        return Err(RpsError::NotAWinner.into());
+    }
+
+    // Save game state before moving funds
+    game.save(game_account)?;
+
+    // Transfer winner's share out of the vault
+    pay_out(&game, game_account.key, vault, winner, accounts_iter, winner_share)?;
+
+    GameEvent::Claimed { game: *game_account.key, player: *winner.key, amount: winner_share }.emit()?;
+
+    msg!("Winnings claimed by: {}", winner.key);
+
+    Ok(())
+}
+
+// Implementation for refunding an entry fee from a cancelled game
+fn process_claim_refund(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let player = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+
+    // Ensure the player signed the transaction
+    if !player.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Load game state
+    let mut game = Game::load(program_id, game_account)?;
+    game.check_vault(program_id, game_account.key, vault)?;
 
-    // Check if game is finished
-    if game.state != GameState::Finished {
-        return Err(ProgramError::InvalidAccountData);
+    // Refunds are only available once the lobby has been cancelled
+    if game.state != GameState::Cancelled {
+        return Err(RpsError::GameNotCancelled.into());
     }
 
-    // Find the winner(s) - those with highest score
-    let mut max_score = 0;
-    for player in &game.players {
-        if player.score > max_score {
-            max_score = player.score;
+    // Every seat in a lobby was paid for with exactly one entry fee; the signer
+    // is refunded for their own seat and every bot seat they funded
+    let mut seats = 0;
+    let mut unclaimed = 0;
+    for player_data in game.players.iter_mut().filter(|p| p.is_owned_by(player.key)) {
+        seats += 1;
+        if !player_data.claimed {
+            player_data.claimed = true;
+            unclaimed += 1;
         }
     }
 
-    let winners: Vec<&Player> = game.players
-        .iter()
-        .filter(|p| p.score == max_score)
-        .collect();
+    if seats == 0 {
+        return Err(RpsError::PlayerNotInGame.into());
+    }
+    if unclaimed == 0 {
+        return Err(RpsError::AlreadyClaimed.into());
+    }
 
-    // Check if caller is among winners
-    let caller_is_winner = winners.iter().any(|p| p.pubkey == *winner.key);
+    let refund = game.entry_fee * unclaimed;
+    game.game_pot = game.game_pot.saturating_sub(refund);
 
-    if !caller_is_winner {
-        return Err(ProgramError::InvalidArgument);
+    // Save game state before moving funds
+    game.save(game_account)?;
+
+    pay_out(&game, game_account.key, vault, player, accounts_iter, refund)?;
+
+    GameEvent::Refunded { game: *game_account.key, player: *player.key, amount: refund }.emit()?;
+
+    msg!("Entry fee refunded to: {}", player.key);
+
+    Ok(())
+}
+
+// Implementation for closing a settled game and reclaiming its rent
+fn process_close_game(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let caller = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let host = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+
+    // Ensure the caller signed the transaction
+    if !caller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
     }
 
-    // Calculate winner's share - all winners take equal share of the pot
-    let winner_share = game.game_pot / winners.len() as u64;
+    let config = ProgramConfig::load(program_id, config_account)?;
 
-    // Transfer winner's share
-    let game_key = game_account.key;
-    let seeds = &[b"rps_game", game_key.as_ref(), &[1]];
-    let (_pda, bump) = Pubkey::find_program_address(seeds, program_id);
-    let signer_seeds = &[b"rps_game", game_key.as_ref(), &[bump][..]];
+    // Load game state
+    let game = Game::load(program_id, game_account)?;
+    game.check_vault(program_id, game_account.key, vault)?;
 
-    invoke_signed(
-        &system_instruction::transfer(
-            game_account.key,
-            winner.key,
-            winner_share,
-        ),
-        &[game_account.clone(), winner.clone(), system_program.clone()],
-        &[signer_seeds],
-    )?;
+    // Bracket matches are closed when their result is recorded
+    if game.tournament != Pubkey::default() {
+        return Err(RpsError::TournamentMatch.into());
+    }
 
-    // Mark player as paid
-    for player in &mut game.players {
-        if player.pubkey == *winner.key {
-            player.score = 0; // Set to 0 to prevent double claiming
-            break;
+    if *host.key != game.host {
+        return Err(RpsError::InvalidHost.into());
+    }
+
+    if *treasury.key != config.treasury {
+        return Err(RpsError::InvalidTreasury.into());
+    }
+
+    // Only settled games can be closed, and only once everyone was paid or the game expired
+    if !matches!(game.state, GameState::Finished | GameState::Cancelled) {
+        return Err(RpsError::GameNotFinished.into());
+    }
+
+    let clock = Clock::get()?;
+    let idle_time = (clock.unix_timestamp as u64).saturating_sub(game.last_action_timestamp);
+    // Games created before the expiry was snapshotted fall back to the minimum
+    let expiry = game.game_expiry_seconds.max(MIN_GAME_EXPIRY_SECONDS);
+    if !game.payouts_complete() && idle_time < expiry {
+        return Err(RpsError::PayoutsPending.into());
+    }
+
+    // Sweep fees, integer-division dust and any expired claims to the treasury,
+    // then hand the vault's rent back to the host
+    match game.currency_mode {
+        CurrencyMode::SOL => {
+            let rent_floor = Rent::get()?.minimum_balance(vault.data_len());
+            let leftover = vault.lamports().saturating_sub(rent_floor);
+            transfer_from_vault(vault, treasury, leftover)?;
+        }
+        CurrencyMode::RPSToken => {
+            let treasury_token_account = next_account_info(accounts_iter)?;
+            let vault_token_account = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+
+            game.check_token_accounts(vault, vault_token_account, token_program)?;
+
+            if *treasury_token_account.key
+                != get_associated_token_address(treasury.key, &game.token_mint)
+            {
+                return Err(RpsError::InvalidTokenAccount.into());
+            }
+
+            let leftover = spl_token::state::Account::unpack(&vault_token_account.data.borrow())?.amount;
+            if leftover > 0 {
+                transfer_tokens_from_vault(
+                    game_account.key,
+                    game.vault_bump,
+                    vault,
+                    vault_token_account,
+                    treasury_token_account,
+                    token_program,
+                    leftover,
+                )?;
+            }
+
+            // Close the vault token account, returning its rent to the host
+            invoke_signed(
+                &spl_token::instruction::close_account(
+                    token_program.key,
+                    vault_token_account.key,
+                    host.key,
+                    vault.key,
+                    &[],
+                )?,
+                &[
+                    vault_token_account.clone(),
+                    host.clone(),
+                    vault.clone(),
+                    token_program.clone(),
+                ],
+                &[&[VAULT_SEED, game_account.key.as_ref(), &[game.vault_bump]]],
+            )?;
         }
     }
 
-    // Save game state
-    game.serialize(&mut *game_account.data.borrow_mut())?;
+    close_account(vault, host)?;
+    close_account(game_account, host)?;
 
-    msg!("Winnings claimed by: {}", winner.key);
+    msg!("Game closed: {}", game_account.key);
 
     Ok(())
 }
@@ -736,6 +1469,7 @@ fn process_rejoin_game(
 
     let player = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
 
     // Ensure the player signed the transaction
@@ -744,42 +1478,43 @@ fn process_rejoin_game(
     }
 
     // Load game state
-    let mut game = Game::try_from_slice(&game_account.data.borrow())?;
+    let mut game = Game::load(program_id, game_account)?;
+    game.check_vault(program_id, game_account.key, vault)?;
 
     // Check if game is in correct state and losers can rejoin
-    if game.state != GameState::Finished || !game.losers_can_rejoin {
-        return Err(ProgramError::InvalidAccountData);
+    if game.state != GameState::Finished {
+        return Err(RpsError::GameNotFinished.into());
+    }
+
+    if !game.losers_can_rejoin {
+        return Err(RpsError::RejoinNotAllowed.into());
     }
 
     // Check if player was a loser in the previous game
     let mut was_player = false;
     let mut was_loser = false;
-    let max_score = game.players.iter().map(|p| p.score).max().unwrap_or(0);
 
     for player_data in &game.players {
         if player_data.pubkey == *player.key {
             was_player = true;
-            if player_data.score < max_score {
+            if !game.is_winner(player_data) {
                 was_loser = true;
             }
             break;
         }
     }
 
-    if !was_player || !was_loser {
-        return Err(ProgramError::InvalidArgument);
+    if !was_player {
+        return Err(RpsError::PlayerNotInGame.into());
+    }
+
+    if !was_loser {
+        return Err(RpsError::NotALoser.into());
     }
 
     // Transfer entry fee
     if game.entry_fee > 0 {
-        invoke(
-            &system_instruction::transfer(
-                player.key,
-                game_account.key,
-                game.entry_fee,
-            ),
-            &[player.clone(), game_account.clone(), system_program.clone()],
-        )?;
+        collect_entry_fee(&game, player, vault, system_program, accounts_iter, game.entry_fee)?;
 
         // Update game pot
         game.game_pot += game.entry_fee;
@@ -788,9 +1523,11 @@ fn process_rejoin_game(
     // Reset this player's stats for the next game
     for player_data in &mut game.players {
         if player_data.pubkey == *player.key {
-            player_data.choice = Choice::None;
+            player_data.choice = Choice::NONE;
             player_data.committed_choice = [0; 32];
             player_data.revealed = false;
+            player_data.eliminated = false;
+            player_data.wagered += game.entry_fee;
             break;
         }
     }
@@ -800,7 +1537,9 @@ fn process_rejoin_game(
     game.last_action_timestamp = clock.unix_timestamp as u64;
 
     // Save game state
-    game.serialize(&mut *game_account.data.borrow_mut())?;
+    game.save(game_account)?;
+
+    GameEvent::PlayerJoined { game: *game_account.key, player: *player.key, is_bot: false }.emit()?;
 
     msg!("Player rejoined game: {}", player.key);
 
@@ -823,11 +1562,16 @@ fn process_start_new_game_round(
     }
 
     // Load game state
-    let mut game = Game::try_from_slice(&game_account.data.borrow())?;
+    let mut game = Game::load(program_id, game_account)?;
+
+    // Bracket matches are single games advanced by their tournament
+    if game.tournament != Pubkey::default() {
+        return Err(RpsError::TournamentMatch.into());
+    }
 
     // Check if game is in finished state
     if game.state != GameState::Finished {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(RpsError::GameNotFinished.into());
     }
 
     // Check if initiator is host or a player
@@ -835,32 +1579,29 @@ fn process_start_new_game_round(
         game.players.iter().any(|p| p.pubkey == *initiator.key);
 
     if !is_participant {
-        return Err(ProgramError::InvalidArgument);
+        return Err(RpsError::NotAParticipant.into());
     }
 
     // Reset game state for a new round
-    game.current_round = 1;
-    game.state = GameState::CommitPhase;
-
-    // Potentially randomize player count again for the new game
     let clock = Clock::get()?;
-    if game.min_players != game.max_players {
-        game.player_count = if (clock.unix_timestamp & 1) == 0 { 3 } else { 4 };
-    }
+    game.current_round = 1;
+    game.begin_commit_phase(clock.unix_timestamp as u64);
 
-    // Reset all players
+    // Reset all players; unclaimed payouts from the previous game stay in the ledger
     for player in &mut game.players {
-        player.choice = Choice::None;
+        player.choice = Choice::NONE;
         player.committed_choice = [0; 32];
         player.revealed = false;
         player.score = 0;
+        player.eliminated = false;
+        player.rounds_won = 0;
     }
 
     // Update last action timestamp
     game.last_action_timestamp = clock.unix_timestamp as u64;
 
     // Save game state
-    game.serialize(&mut *game_account.data.borrow_mut())?;
+    game.save(game_account)?;
 
     msg!("New game round started");
 
@@ -883,21 +1624,21 @@ fn process_auto_play_next_round(
     }
 
     // Load game state
-    let mut game = Game::try_from_slice(&game_account.data.borrow())?;
+    let mut game = Game::load(program_id, game_account)?;
 
     // Check if game is in automated mode
     if !matches!(game.game_mode, GameMode::Automated) {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(RpsError::NotAutomatedGame.into());
     }
 
     // Check if game is in finished state
     if !matches!(game.state, GameState::Finished) {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(RpsError::GameNotFinished.into());
     }
 
     // Check if we've reached the maximum number of auto rounds
     if game.current_auto_round >= game.max_auto_rounds {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(RpsError::MaxAutoRoundsReached.into());
     }
 
     // Check if initiator is host or a player
@@ -905,33 +1646,30 @@ fn process_auto_play_next_round(
         game.players.iter().any(|p| p.pubkey == *initiator.key);
 
     if !is_participant {
-        return Err(ProgramError::InvalidArgument);
+        return Err(RpsError::NotAParticipant.into());
     }
 
     // Reset game state for a new round
+    let clock = Clock::get()?;
     game.current_round = 1;
-    game.state = GameState::CommitPhase;
+    game.begin_commit_phase(clock.unix_timestamp as u64);
     game.current_auto_round += 1;
 
-    // Potentially randomize player count again for the new game
-    let clock = Clock::get()?;
-    if game.min_players != game.max_players {
-        game.player_count = if (clock.unix_timestamp & 1) == 0 { 3 } else { 4 };
-    }
-
-    // Reset all players
+    // Reset all players; unclaimed payouts from the previous game stay in the ledger
     for player in &mut game.players {
-        player.choice = Choice::None;
+        player.choice = Choice::NONE;
         player.committed_choice = [0; 32];
         player.revealed = false;
         player.score = 0;
+        player.eliminated = false;
+        player.rounds_won = 0;
     }
 
     // Update last action timestamp
     game.last_action_timestamp = clock.unix_timestamp as u64;
 
     // Save game state
-    game.serialize(&mut *game_account.data.borrow_mut())?;
+    game.save(game_account)?;
 
     msg!("New automated game round started");
 
@@ -948,6 +1686,7 @@ fn process_add_bot_players(
 
     let initiator = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
 
     // Ensure the initiator signed the transaction
@@ -956,112 +1695,748 @@ fn process_add_bot_players(
     }
 
     // Load game state
-    let mut game = Game::try_from_slice(&game_account.data.borrow())?;
+    let mut game = Game::load(program_id, game_account)?;
+    game.check_vault(program_id, game_account.key, vault)?;
 
     // Check if game is in correct state
     if !matches!(game.state, GameState::WaitingForPlayers) {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(RpsError::GameNotJoinable.into());
+    }
+
+    // Bots would take the seats of a private game's invitees unless the host adds them
+    if game.access.is_private() && game.host != *initiator.key {
+        return Err(RpsError::NotInvited.into());
+    }
+
+    // A duel with a named opponent has no seat left for a bot
+    if game.opponent != Pubkey::default() {
+        return Err(RpsError::NotInvitedOpponent.into());
     }
 
     // Check if there's room for bot players
-    let available_slots = game.player_count as usize - game.players.len();
+    let available_slots = game.open_seats();
     let bot_count = std::cmp::min(count as usize, available_slots);
 
     if bot_count == 0 {
-        return Err(ProgramError::InvalidArgument);
+        return Err(RpsError::GameFull.into());
     }
 
-    // Add bot players
-    for i in 0..bot_count {
-        // Create a deterministic bot pubkey based on game account and index
-        let seed = format!("bot_{}_{}_{}", game_account.key, game.players.len(), i);
-        let bot_pubkey = Pubkey::new(seed.as_bytes());
+    // Add bot players; seating stops early if the player count draw closes the lobby
+    let clock = Clock::get()?;
+    let mut bots_added = 0;
+    for _ in 0..bot_count {
+        if game.state != GameState::WaitingForPlayers {
+            break;
+        }
 
-        game.players.push(Player {
-            pubkey: bot_pubkey,
-            choice: Choice::None,
-            committed_choice: [0; 32],
-            revealed: false,
-            score: 0,
-        });
+        // Bot identities are PDAs of the game and the seat they occupy
+        let (bot_pubkey, _) = find_bot_address(game_account.key, game.players.len() as u8, program_id);
 
-        // Update game pot for bot players - simulate them paying entry fee
-        game.game_pot += game.entry_fee;
+        let bot = Player::bot(bot_pubkey, *initiator.key);
+        GameEvent::player_joined(game_account.key, &bot).emit()?;
+        seat_player(&mut game, bot, clock.unix_timestamp as u64);
+        bots_added += 1;
 
         msg!("Added bot player: {}", bot_pubkey);
     }
 
-    // Update game state if required player count is reached
+    // Update last action timestamp
+    game.last_action_timestamp = clock.unix_timestamp as u64;
+
+    // Save game state
+    game.save(game_account)?;
+
+    // The initiator pays every bot seat's entry fee into the vault
+    let amount = game.entry_fee * bots_added as u64;
+    collect_entry_fee(&game, initiator, vault, system_program, accounts_iter, amount)?;
+
+    // A game that filled up leaves the open lobby
+    if let (Some(lobby_account), _) = split_lobby(program_id, accounts) {
+        lobby::update_listing(program_id, lobby_account, game_account.key, &game)?;
+    }
+
+    msg!("Added {} bot players", bots_added);
+
+    Ok(())
+}
+
+// Implementation for revealing the host's player count seed
+fn process_reveal_count_seed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seed: [u8; 32],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let host = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+
+    // Ensure the host signed the transaction
+    if !host.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut game = Game::load(program_id, game_account)?;
+    if *host.key != game.host {
+        return Err(RpsError::InvalidHost.into());
+    }
+
+    if game.state != GameState::WaitingForPlayers {
+        return Err(RpsError::GameNotJoinable.into());
+    }
+
+    // Revealing before the minimum is seated would let the last joiner steer the draw
+    if game.players.len() < game.min_players as usize {
+        return Err(RpsError::CountSeedNotDue.into());
+    }
+
+    if game.count_seed_hash == [0; 32] || count_seed_hash(game_account.key, &seed) != game.count_seed_hash {
+        return Err(RpsError::CountSeedMismatch.into());
+    }
+
+    // A host who held the seed back too long can no longer pick the moment
+    let clock = Clock::get()?;
+    if clock.unix_timestamp as u64 >= game.count_seed_deadline {
+        return Err(RpsError::CountSeedDeadlinePassed.into());
+    }
+    game.count_seed_hash = [0; 32];
+
+    let mut rng = GameRng::new(&game.entropy, &seed);
+    game.draw_player_count(&mut rng);
+    msg!("Player count drawn: {}", game.player_count);
+
     if game.players.len() >= game.player_count as usize {
-        game.state = GameState::CommitPhase;
+        game.begin_commit_phase(clock.unix_timestamp as u64);
         msg!("Required player count reached: {}", game.player_count);
     }
 
-    // Update last action timestamp
-    let clock = Clock::get()?;
     game.last_action_timestamp = clock.unix_timestamp as u64;
+    game.save(game_account)?;
 
-    // Save game state
-    game.serialize(&mut *game_account.data.borrow_mut())?;
+    // A game that starts with the seated players leaves the open lobby
+    if let (Some(lobby_account), _) = split_lobby(program_id, accounts) {
+        lobby::update_listing(program_id, lobby_account, game_account.key, &game)?;
+    }
 
-    msg!("Added {} bot players", bot_count);
+    Ok(())
+}
+
+// Helper function to seat a player in the lobby and charge the entry fee to the pot.
+// Lobbies with a range of sizes open the seats past the minimum once the host
+// reveals the player count seed, which is due one reveal timeout after the
+// minimum is seated. The game starts as soon as every seat is taken.
+fn seat_player(
+    game: &mut Game,
+    mut player: Player,
+    now: u64,
+) {
+    mix_entropy(&mut game.entropy, &[player.pubkey.as_ref()]);
+    player.wagered = game.entry_fee;
+    game.players.push(player);
+    game.game_pot += game.entry_fee;
+
+    let seed_pending = game.count_seed_hash != [0; 32];
+    if seed_pending && game.players.len() == game.min_players as usize {
+        game.count_seed_deadline = now.saturating_add(game.reveal_timeout);
+    }
+
+    if !seed_pending && game.players.len() >= game.player_count as usize {
+        game.begin_commit_phase(now);
+        msg!("Required player count reached: {}", game.player_count);
+    }
+}
+
+// Helper function to close a round once every human has revealed or timed out:
+// bots play, the round is scored and the game either ends or moves on
+fn complete_round(game: &mut Game, game_key: &Pubkey, now: u64) -> ProgramResult {
+    if game.players.iter().any(|p| p.is_bot) {
+        play_bot_moves(game, game_key);
+    }
+
+    // Calculate round winners
+    let scores_before: Vec<u8> = game.players.iter().map(|p| p.score).collect();
+    process_round_results(game);
+
+    if matches!(game.game_mode, GameMode::BattleRoyale) {
+        eliminate_round_losers(game, &scores_before);
+    }
+
+    GameEvent::round_resolved(game_key, game).emit()?;
+
+    // Check if game should end; a battle royale runs until only the survivors
+    // are left and a duel until someone has won a majority, drawn rounds
+    // being replayed
+    let game_over = match game.game_mode {
+        GameMode::BattleRoyale => {
+            game.active_player_count() <= game.survivors as usize || game.current_round == u8::MAX
+        }
+        GameMode::Duel => {
+            let wins_needed = game.total_rounds / 2 + 1;
+            game.players.iter().any(|p| p.score >= wins_needed) || game.current_round == u8::MAX
+        }
+        _ => game.current_round >= game.total_rounds,
+    };
+
+    if game_over {
+        finish_game(game);
+    } else {
+        // Reset for next round
+        game.current_round += 1;
+        game.begin_commit_phase(now);
+
+        // Reset player choices for next round
+        for player in &mut game.players {
+            player.choice = Choice::NONE;
+            player.committed_choice = [0; 32];
+            player.revealed = false;
+        }
+    }
 
     Ok(())
 }
 
+// Helper function to knock out the players with the lowest score this round.
+// Nobody is knocked out when everyone tied or when fewer than `survivors`
+// would be left; the round is then simply played again.
+fn eliminate_round_losers(game: &mut Game, scores_before: &[u8]) {
+    let round_scores: Vec<Option<u8>> = game.players
+        .iter()
+        .zip(scores_before)
+        .map(|(p, before)| (!p.eliminated).then(|| p.score - before))
+        .collect();
+
+    let lowest = round_scores.iter().flatten().min().copied();
+    let highest = round_scores.iter().flatten().max().copied();
+    let (Some(lowest), Some(highest)) = (lowest, highest) else {
+        return;
+    };
+    if lowest == highest {
+        return;
+    }
+
+    let knocked_out = round_scores.iter().filter(|s| **s == Some(lowest)).count();
+    if game.active_player_count() - knocked_out < game.survivors as usize {
+        return;
+    }
+
+    for (player, score) in game.players.iter_mut().zip(round_scores) {
+        if score == Some(lowest) {
+            player.eliminated = true;
+        }
+    }
+}
+
+// Helper function to draw bot moves. The pool took a slot hash when the commit
+// phase closed and holds every salt revealed since, so the draw was fixed
+// before anyone revealed and neither the last revealer nor whoever resolves
+// the timeout can steer it.
+fn play_bot_moves(game: &mut Game, game_key: &Pubkey) {
+    let mut rng = GameRng::new(&game.entropy, &game_key.to_bytes());
+    let choice_count = game.rule_set.choice_count() as u64;
+
+    for player in game.players.iter_mut().filter(|p| p.is_bot && !p.eliminated) {
+        player.choice = Choice::new(rng.below(choice_count) as u8 + 1);
+        player.revealed = true;
+    }
+}
+
 // Helper function to process round results
 fn process_round_results(game: &mut Game) {
-    let player_count = game.players.len();
-
-    // For each player, compare against every other player
-    for i in 0..player_count {
-        for j in (i+1)..player_count {
-            let choice_i = &game.players[i].choice;
-            let choice_j = &game.players[j].choice;
-
-            match (choice_i, choice_j) {
-                (Choice::Rock, Choice::Scissors) |
-                (Choice::Paper, Choice::Rock) |
-                (Choice::Scissors, Choice::Paper) => {
-                    // Player i wins against player j
-                    game.players[i].score += 1;
-                },
-                (Choice::Scissors, Choice::Rock) |
-                (Choice::Rock, Choice::Paper) |
-                (Choice::Paper, Choice::Scissors) => {
-                    // Player j wins against player i
-                    game.players[j].score += 1;
-                },
-                _ => {
-                    // Tie or invalid choices - no points awarded
-                }
+    // Players knocked out of a battle royale sit the round out
+    let choices: Vec<Option<Choice>> = game.players
+        .iter()
+        .map(|p| (!p.eliminated).then_some(p.choice))
+        .collect();
+
+    let points = game.scoring_model.round_points(&game.rule_set, &choices);
+    for (player, points) in game.players.iter_mut().zip(points) {
+        player.score = player.score.saturating_add(points);
+        if points > 0 {
+            player.rounds_won = player.rounds_won.saturating_add(1);
+        }
+    }
+}
+
+// Helper function to finish a game: the protocol fee moves out of the pot and
+// the rest is frozen into payouts following the game's payout table. Integer-division dust stays in
+// the pot until the game is closed.
+fn finish_game(game: &mut Game) {
+    let fee = calculate_fee(game.game_pot, game.fee_basis_points);
+    game.game_pot -= fee;
+    game.fees_owed += fee;
+
+    // Every survivor of a battle royale ranks equal; eliminated players rank nowhere
+    let standings: Vec<Option<u8>> = game.players
+        .iter()
+        .map(|p| match game.game_mode {
+            _ if p.eliminated => None,
+            GameMode::BattleRoyale => Some(0),
+            _ => Some(p.score),
+        })
+        .collect();
+
+    let shares = game.payout_table.split(game.game_pot, &standings);
+    for (player, share) in game.players.iter_mut().zip(shares) {
+        player.winnings = share;
+        if share > 0 {
+            player.payout += share;
+            player.claimed = false;
+            game.game_pot -= share;
+        }
+    }
+
+    game.state = GameState::Finished;
+}
+
+// Helper function to close a program-owned account: its lamports go to the
+// recipient and its data is zeroed so it can never be loaded again
+fn close_account(account: &AccountInfo, recipient: &AccountInfo) -> ProgramResult {
+    let lamports = account.lamports();
+    **account.try_borrow_mut_lamports()? = 0;
+    **recipient.try_borrow_mut_lamports()? = recipient
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    account.data.borrow_mut().fill(0);
+
+    Ok(())
+}
+
+// Derive the identity of the bot seated at `seat` in `game`
+pub fn find_bot_address(game: &Pubkey, seat: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BOT_SEED, game.as_ref(), &[seat]], program_id)
+}
+
+// Derive the vault PDA that escrows the pot of a game
+pub fn find_vault_address(game: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, game.as_ref()], program_id)
+}
+
+// Helper function to move lamports from a signer into a game vault
+fn transfer_to_vault<'a>(
+    payer: &AccountInfo<'a>,
+    vault: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    invoke(
+        &system_instruction::transfer(payer.key, vault.key, amount),
+        &[payer.clone(), vault.clone(), system_program.clone()],
+    )
+}
+
+// Helper function to pay lamports out of a game vault.
+// The vault is owned by this program, so it is debited directly.
+fn transfer_from_vault(
+    vault: &AccountInfo,
+    recipient: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    let vault_balance = vault.lamports();
+    let rent_floor = Rent::get()?.minimum_balance(vault.data_len());
+    if vault_balance.saturating_sub(rent_floor) < amount {
+        return Err(RpsError::InsufficientVaultFunds.into());
+    }
+
+    **vault.try_borrow_mut_lamports()? = vault_balance - amount;
+    **recipient.try_borrow_mut_lamports()? = recipient
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+// Helper function to move tokens from a signer's token account into the vault token account
+fn transfer_tokens_to_vault<'a>(
+    owner: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    vault_token_account: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    invoke(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            source.key,
+            vault_token_account.key,
+            owner.key,
+            &[],
+            amount,
+        )?,
+        &[
+            source.clone(),
+            vault_token_account.clone(),
+            owner.clone(),
+            token_program.clone(),
+        ],
+    )
+}
+
+// Helper function to pay tokens out of the vault token account, signed by the vault PDA
+fn transfer_tokens_from_vault<'a>(
+    game_key: &Pubkey,
+    vault_bump: u8,
+    vault: &AccountInfo<'a>,
+    vault_token_account: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            vault_token_account.key,
+            destination.key,
+            vault.key,
+            &[],
+            amount,
+        )?,
+        &[
+            vault_token_account.clone(),
+            destination.clone(),
+            vault.clone(),
+            token_program.clone(),
+        ],
+        &[&[VAULT_SEED, game_key.as_ref(), &[vault_bump]]],
+    )
+}
+
+// Helper function to collect an entry fee into the game's escrow.
+// RPSToken games read the payer token account, vault token account and
+// token program from the remaining accounts.
+fn collect_entry_fee<'a, 'b>(
+    game: &Game,
+    payer: &'b AccountInfo<'a>,
+    vault: &'b AccountInfo<'a>,
+    system_program: &'b AccountInfo<'a>,
+    accounts_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    amount: u64,
+) -> ProgramResult {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    match game.currency_mode {
+        CurrencyMode::SOL => transfer_to_vault(payer, vault, system_program, amount),
+        CurrencyMode::RPSToken => {
+            let payer_token_account = next_account_info(accounts_iter)?;
+            let vault_token_account = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+
+            game.check_token_accounts(vault, vault_token_account, token_program)?;
+
+            transfer_tokens_to_vault(
+                payer,
+                payer_token_account,
+                vault_token_account,
+                token_program,
+                amount,
+            )
+        }
+    }
+}
+
+// Helper function to pay out of the game's escrow.
+// RPSToken games read the recipient's associated token account, vault token
+// account and token program from the remaining accounts.
+fn pay_out<'a, 'b>(
+    game: &Game,
+    game_key: &Pubkey,
+    vault: &'b AccountInfo<'a>,
+    recipient: &'b AccountInfo<'a>,
+    accounts_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    amount: u64,
+) -> ProgramResult {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    match game.currency_mode {
+        CurrencyMode::SOL => transfer_from_vault(vault, recipient, amount),
+        CurrencyMode::RPSToken => {
+            let recipient_token_account = next_account_info(accounts_iter)?;
+            let vault_token_account = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+
+            game.check_token_accounts(vault, vault_token_account, token_program)?;
+
+            // Payouts only ever go to the recipient's own associated token account
+            if *recipient_token_account.key
+                != get_associated_token_address(recipient.key, &game.token_mint)
+            {
+                return Err(RpsError::InvalidTokenAccount.into());
             }
+
+            transfer_tokens_from_vault(
+                game_key,
+                game.vault_bump,
+                vault,
+                vault_token_account,
+                recipient_token_account,
+                token_program,
+                amount,
+            )
+        }
+    }
+}
+
+impl Player {
+    pub const LEN: usize = 32 + // pubkey
+        1 +  // choice
+        32 + // committed_choice
+        1 +  // revealed
+        1 +  // score
+        1 +  // claimed
+        8 +  // payout
+        1 +  // is_bot
+        32 + // funder
+        1 +  // eliminated
+        1 +  // rounds_won
+        8 +  // wagered
+        8;   // winnings
+
+    pub fn new(pubkey: Pubkey) -> Self {
+        Player {
+            pubkey,
+            choice: Choice::NONE,
+            committed_choice: [0; 32],
+            revealed: false,
+            score: 0,
+            claimed: false,
+            payout: 0,
+            is_bot: false,
+            funder: Pubkey::default(),
+            eliminated: false,
+            rounds_won: 0,
+            wagered: 0,
+            winnings: 0,
         }
     }
+
+    pub fn bot(pubkey: Pubkey, funder: Pubkey) -> Self {
+        Player {
+            is_bot: true,
+            funder,
+            ..Player::new(pubkey)
+        }
+    }
+
+    // Whether `wallet` collects this seat's payouts and refunds
+    pub fn is_owned_by(&self, wallet: &Pubkey) -> bool {
+        self.pubkey == *wallet || (self.is_bot && self.funder == *wallet)
+    }
+
+    // Whether this seat let the commit phase run out; bots never commit
+    pub fn missed_commit(&self) -> bool {
+        !self.is_bot && !self.eliminated && self.committed_choice == [0; 32]
+    }
 }
 
 // Helper methods for Game struct
 impl Game {
-    pub fn get_max_size(max_players: u8) -> usize {
-        // Calculate max size needed for serialized Game struct with max_players
-        // This is a rough estimate - actual implementation would need precise calculation
-        8 + // host pubkey
-        4 + (max_players as usize * 32 + 1 + 32 + 1 + 1) + // Vector of Player structs
-        1 + // min_players
-        1 + // max_players
-        1 + // game state
-        1 + // current_round
-        1 + // total_rounds
-        8 + // entry_fee
-        8 + // game_pot
-        8 + // required_timeout
-        8 + // last_action_timestamp
-        1 + // player_count
-        1 + // losers_can_rejoin
-        1 + // game_mode
-        8 + // auto_round_delay
-        8 + // max_auto_rounds
-        8 + // current_auto_round
-        1   // currency_mode
+    // Load a game from an account owned by this program.
+    // Accounts are allocated at their maximum size, so trailing bytes are ignored.
+    pub fn load(program_id: &Pubkey, game_account: &AccountInfo) -> Result<Self, ProgramError> {
+        if game_account.owner != program_id {
+            return Err(RpsError::InvalidGameAccount.into());
+        }
+
+        let data = game_account.data.borrow();
+        if !AccountType::Game.tags(&data) || data.get(1).map_or(true, |version| *version > GAME_LAYOUT_VERSION) {
+            return Err(RpsError::InvalidGameAccount.into());
+        }
+        Ok(Self::deserialize(&mut &data[..])?)
+    }
+
+    // Draw the number of players uniformly between min_players and max_players
+    pub fn draw_player_count(&mut self, rng: &mut GameRng) {
+        let range = (self.max_players - self.min_players + 1) as u64;
+        self.player_count = self.min_players + rng.below(range) as u8;
+    }
+
+    // Seats a joiner can take now; until the player count seed is revealed only
+    // the minimum is open
+    pub fn open_seats(&self) -> usize {
+        let seats = if self.count_seed_hash != [0; 32] { self.min_players } else { self.player_count };
+        (seats as usize).saturating_sub(self.players.len())
+    }
+
+    // When a lobby still waiting for players times out: after a period of
+    // inactivity, or once the host let the player count seed go unrevealed
+    pub fn lobby_deadline(&self) -> u64 {
+        let idle_deadline = self.last_action_timestamp.saturating_add(self.required_timeout);
+        if self.count_seed_hash != [0; 32] && self.count_seed_deadline != 0 {
+            idle_deadline.min(self.count_seed_deadline)
+        } else {
+            idle_deadline
+        }
+    }
+
+    // Enter the commit phase; its deadline is fixed now and not moved by later actions
+    pub fn begin_commit_phase(&mut self, now: u64) {
+        self.state = GameState::CommitPhase;
+        self.commit_deadline = now.saturating_add(self.commit_timeout);
+        self.reveal_deadline = 0;
+    }
+
+    // Enter the reveal phase; its deadline is fixed now and not moved by later actions
+    // The newest slot hash goes into the pool here to seed the bots' moves, see play_bot_moves
+    pub fn begin_reveal_phase(&mut self, now: u64, slot_hash: &[u8; 32]) {
+        mix_entropy(&mut self.entropy, &[slot_hash]);
+        self.state = GameState::RevealPhase;
+        self.reveal_deadline = now.saturating_add(self.reveal_timeout);
+    }
+
+    // Whether joining requires a rating within [min_rating, max_rating]
+    pub fn has_rating_band(&self) -> bool {
+        self.min_rating != 0 || self.max_rating != 0
+    }
+
+    // Whether a player rated `rating` may join
+    pub fn accepts_rating(&self, rating: u16) -> bool {
+        rating >= self.min_rating && (self.max_rating == 0 || rating <= self.max_rating)
+    }
+
+    // Players not knocked out of a battle royale
+    pub fn active_player_count(&self) -> usize {
+        self.players.iter().filter(|p| !p.eliminated).count()
+    }
+
+    // Whether `player` wins the game as it stands: every survivor of a battle
+    // royale, otherwise the top scorers among those still in
+    pub fn is_winner(&self, player: &Player) -> bool {
+        if player.eliminated {
+            return false;
+        }
+
+        match self.game_mode {
+            GameMode::BattleRoyale => true,
+            _ => {
+                let max_score = self.players.iter().filter(|p| !p.eliminated).map(|p| p.score).max();
+                Some(player.score) == max_score
+            }
+        }
+    }
+
+    // Funds the vault must hold for this game: the pot, fees awaiting withdrawal
+    // and settled payouts awaiting claims
+    pub fn escrowed_total(&self) -> u64 {
+        self.game_pot + self.fees_owed + self.players.iter().map(|p| p.payout).sum::<u64>()
+    }
+
+    // Whether every refund or winning owed by this game has been withdrawn
+    pub fn payouts_complete(&self) -> bool {
+        match self.state {
+            GameState::Cancelled => self.players.iter().all(|p| p.claimed),
+            GameState::Finished => {
+                self.fees_owed == 0
+                    && self.players.iter().all(|p| p.payout == 0)
+                    && self.players.iter().all(|p| p.wagered == 0)
+            },
+            _ => false,
+        }
+    }
+
+    // Save the game, zeroing whatever a longer earlier save left behind it
+    pub fn save(&self, game_account: &AccountInfo) -> ProgramResult {
+        let mut data = game_account.data.borrow_mut();
+        let mut tail: &mut [u8] = &mut data[..];
+        self.serialize(&mut tail)?;
+        tail.fill(0);
+        Ok(())
+    }
+
+    // Ensure the supplied vault is the PDA recorded for this game
+    pub fn check_vault(
+        &self,
+        program_id: &Pubkey,
+        game_key: &Pubkey,
+        vault: &AccountInfo,
+    ) -> ProgramResult {
+        let expected = Pubkey::create_program_address(
+            &[VAULT_SEED, game_key.as_ref(), &[self.vault_bump]],
+            program_id,
+        )
+        .map_err(|_| RpsError::InvalidVault)?;
+
+        if expected != *vault.key {
+            return Err(RpsError::InvalidVault.into());
+        }
+
+        Ok(())
+    }
+
+    // Ensure the token accounts supplied for an RPSToken game are the vault's ATA and the SPL Token program
+    pub fn check_token_accounts(
+        &self,
+        vault: &AccountInfo,
+        vault_token_account: &AccountInfo,
+        token_program: &AccountInfo,
+    ) -> ProgramResult {
+        if *token_program.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if *vault_token_account.key != get_associated_token_address(vault.key, &self.token_mint) {
+            return Err(RpsError::InvalidTokenAccount.into());
+        }
+
+        Ok(())
+    }
+
+    // Serialized size of the fixed fields, i.e. everything except the players
+    pub const BASE_LEN: usize = 1 + // account_type
+        1 +  // layout_version
+        32 + // host
+        4 +  // players length prefix
+        1 +  // min_players
+        1 +  // max_players
+        1 +  // state
+        1 +  // current_round
+        1 +  // total_rounds
+        8 +  // entry_fee
+        8 +  // game_pot
+        8 +  // required_timeout
+        8 +  // last_action_timestamp
+        1 +  // player_count
+        1 +  // losers_can_rejoin
+        1 +  // game_mode
+        8 +  // auto_round_delay
+        8 +  // max_auto_rounds
+        8 +  // current_auto_round
+        1 +  // currency_mode
+        1 +  // vault_bump
+        32 + // token_mint
+        2 +  // fee_basis_points
+        8 +  // fees_owed
+        8 +  // commit_timeout
+        8 +  // reveal_timeout
+        8 +  // commit_deadline
+        8 +  // reveal_deadline
+        32 + // entropy
+        32 + // tournament
+        1 +  // survivors
+        32 + // opponent
+        RuleSet::MAX_LEN + // rule_set
+        1 +  // scoring_model
+        PayoutTable::MAX_LEN + // payout_table
+        2 +  // min_rating
+        2 +  // max_rating
+        GameAccess::MAX_LEN + // access
+        32 + // count_seed_hash
+        8 +  // game_expiry_seconds
+        8;   // count_seed_deadline
+
+    // Exact serialized size of a game holding `max_players` players
+    pub fn serialized_len(max_players: u8) -> usize {
+        Self::BASE_LEN + max_players as usize * Player::LEN
+    }
+
+    // Space allocated for a game account, with room for fields appended later;
+    // see GAME_RESERVED_SPACE
+    pub fn space(max_players: u8) -> usize {
+        Self::serialized_len(max_players) + GAME_RESERVED_SPACE
     }
 }
@@ -0,0 +1,252 @@
+// Registry of open games, so clients can list joinable tables with a single
+// account fetch. The lobby is one PDA holding a bounded list of entries.
+// Instructions that open or close a lobby take the registry as their optional
+// last account: InitializeGame lists the new game, and JoinGame,
+// AddBotPlayers, ResolveTimeout and RevealChoice delist it once it stops
+// waiting for players. PruneLobby clears entries left behind when the
+// registry was not passed, and those of lobbies that timed out.
+//
+// So the bounded list cannot be squatted cheaply, a listed game must time out
+// within MAX_LISTED_TIMEOUT and its host leaves LISTING_DEPOSIT in the game
+// account, returned with the account's rent by CloseGame.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{clock::Clock, rent::Rent, Sysvar},
+};
+
+use crate::{error::RpsError, CurrencyMode, Game, GameMode, GameState};
+
+// Seed of the open-lobby registry PDA
+pub const LOBBY_SEED: &[u8] = b"lobby";
+
+// Most games listed at once
+pub const LOBBY_CAPACITY: usize = 128;
+
+// Longest a listed game may wait for players before it can be pruned
+pub const MAX_LISTED_TIMEOUT: u64 = 3_600;
+
+// Lamports a host leaves in a listed game's account until the game is closed
+pub const LISTING_DEPOSIT: u64 = 50_000_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct LobbyEntry {
+    pub game: Pubkey,
+    pub entry_fee: u64,
+    pub currency_mode: CurrencyMode,
+    pub game_mode: GameMode,
+}
+
+impl LobbyEntry {
+    pub const LEN: usize = 32 + // game
+        8 +  // entry_fee
+        1 +  // currency_mode
+        1;   // game_mode
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Lobby {
+    pub bump: u8,               // Bump seed of the lobby PDA
+    pub games: Vec<LobbyEntry>, // Listing order, oldest first
+}
+
+// Client-side filter over the listed games; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct LobbyFilter {
+    pub max_entry_fee: Option<u64>,
+    pub currency_mode: Option<CurrencyMode>,
+    pub game_mode: Option<GameMode>,
+}
+
+impl LobbyFilter {
+    pub fn matches(&self, entry: &LobbyEntry) -> bool {
+        self.max_entry_fee.map_or(true, |fee| entry.entry_fee <= fee)
+            && self.currency_mode.as_ref().map_or(true, |mode| *mode == entry.currency_mode)
+            && self.game_mode.as_ref().map_or(true, |mode| *mode == entry.game_mode)
+    }
+}
+
+impl Lobby {
+    pub const SPACE: usize = 1 + // bump
+        4 + LOBBY_CAPACITY * LobbyEntry::LEN; // games
+
+    // Load the registry, ensuring the account is the lobby PDA
+    pub fn load(program_id: &Pubkey, lobby_account: &AccountInfo) -> Result<Self, ProgramError> {
+        if lobby_account.owner != program_id {
+            return Err(RpsError::InvalidLobbyAccount.into());
+        }
+
+        let lobby = Self::deserialize(&mut &lobby_account.data.borrow()[..])?;
+
+        let expected = Pubkey::create_program_address(&[LOBBY_SEED, &[lobby.bump]], program_id)
+            .map_err(|_| RpsError::InvalidLobbyAccount)?;
+        if expected != *lobby_account.key {
+            return Err(RpsError::InvalidLobbyAccount.into());
+        }
+
+        Ok(lobby)
+    }
+
+    pub fn save(&self, lobby_account: &AccountInfo) -> ProgramResult {
+        self.serialize(&mut &mut lobby_account.data.borrow_mut()[..])?;
+        Ok(())
+    }
+
+    // Listed games passing `filter`, oldest first
+    pub fn open_games<'a>(&'a self, filter: &'a LobbyFilter) -> impl Iterator<Item = &'a LobbyEntry> {
+        self.games.iter().filter(move |entry| filter.matches(entry))
+    }
+
+    fn delist(&mut self, game: &Pubkey) -> bool {
+        let before = self.games.len();
+        self.games.retain(|entry| entry.game != *game);
+        self.games.len() != before
+    }
+}
+
+// Derive the open-lobby registry PDA
+pub fn find_lobby_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LOBBY_SEED], program_id)
+}
+
+// Split the registry off the end of an instruction's accounts, if it was passed
+pub(crate) fn split_lobby<'a, 'b>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'b>],
+) -> (Option<&'a AccountInfo<'b>>, &'a [AccountInfo<'b>]) {
+    match accounts.split_last() {
+        Some((last, rest)) if *last.key == find_lobby_address(program_id).0 => (Some(last), rest),
+        _ => (None, accounts),
+    }
+}
+
+// List a newly created game, taking the host's listing deposit
+pub(crate) fn list_game<'a>(
+    program_id: &Pubkey,
+    lobby_account: &AccountInfo<'a>,
+    host: &AccountInfo<'a>,
+    game_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    game: &Game,
+) -> ProgramResult {
+    if game.required_timeout > MAX_LISTED_TIMEOUT {
+        return Err(RpsError::ListedTimeoutTooLong.into());
+    }
+
+    let mut lobby = Lobby::load(program_id, lobby_account)?;
+    if lobby.games.len() >= LOBBY_CAPACITY {
+        return Err(RpsError::LobbyFull.into());
+    }
+
+    invoke(
+        &system_instruction::transfer(host.key, game_account.key, LISTING_DEPOSIT),
+        &[host.clone(), game_account.clone(), system_program.clone()],
+    )?;
+
+    lobby.games.push(LobbyEntry {
+        game: *game_account.key,
+        entry_fee: game.entry_fee,
+        currency_mode: game.currency_mode.clone(),
+        game_mode: game.game_mode.clone(),
+    });
+    lobby.save(lobby_account)
+}
+
+// Delist a game once it no longer waits for players
+pub(crate) fn update_listing(
+    program_id: &Pubkey,
+    lobby_account: &AccountInfo,
+    game_key: &Pubkey,
+    game: &Game,
+) -> ProgramResult {
+    if game.state == GameState::WaitingForPlayers {
+        return Ok(());
+    }
+
+    let mut lobby = Lobby::load(program_id, lobby_account)?;
+    if lobby.delist(game_key) {
+        lobby.save(lobby_account)?;
+    }
+    Ok(())
+}
+
+// Implementation for creating the open-lobby registry; anyone may pay its rent
+pub fn process_initialize_lobby(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let payer = next_account_info(accounts_iter)?;
+    let lobby_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // Ensure the payer signed the transaction
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (lobby_key, bump) = find_lobby_address(program_id);
+    if lobby_key != *lobby_account.key {
+        return Err(RpsError::InvalidLobbyAccount.into());
+    }
+
+    let rent = Rent::get()?;
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            lobby_account.key,
+            rent.minimum_balance(Lobby::SPACE),
+            Lobby::SPACE as u64,
+            program_id,
+        ),
+        &[payer.clone(), lobby_account.clone(), system_program.clone()],
+        &[&[LOBBY_SEED, &[bump]]],
+    )?;
+
+    let lobby = Lobby { bump, games: Vec::new() };
+    lobby.save(lobby_account)?;
+
+    msg!("Open lobby registry created");
+
+    Ok(())
+}
+
+// Implementation for removing listed games that are no longer open: games that
+// left the lobby phase or whose lobby timed out, and closed game accounts
+pub fn process_prune_lobby(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let lobby_account = next_account_info(accounts_iter)?;
+    let mut lobby = Lobby::load(program_id, lobby_account)?;
+    let now = Clock::get()?.unix_timestamp as u64;
+
+    let mut pruned = 0;
+    for game_account in accounts_iter {
+        let open = Game::load(program_id, game_account)
+            .map(|game| {
+                game.state == GameState::WaitingForPlayers && now < game.lobby_deadline()
+            })
+            .unwrap_or(false);
+
+        if !open && lobby.delist(game_account.key) {
+            pruned += 1;
+        }
+    }
+
+    lobby.save(lobby_account)?;
+
+    msg!("Pruned {} games from the lobby", pruned);
+
+    Ok(())
+}
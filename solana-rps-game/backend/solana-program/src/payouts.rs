@@ -0,0 +1,92 @@
+// Payout tables splitting a settled pot between players. Players are ranked
+// by score; players tied on score share the places they occupy, so the split
+// never depends on seat order. Integer-division dust is left unassigned.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::program_error::ProgramError;
+
+use crate::{config::BASIS_POINTS_DENOMINATOR, error::RpsError};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum PayoutTable {
+    // The top score takes the pot
+    WinnerTakeAll,
+    // First and second place take fixed shares of the pot
+    TopTwo { first_bps: u16, second_bps: u16 },
+    // Each player takes a share of the pot proportional to their score
+    ProportionalToScore,
+}
+
+impl PayoutTable {
+    // Serialized size of the largest table
+    pub const MAX_LEN: usize = 1 + 2 + 2;
+
+    // Shares must cover the whole pot and first place must not get less than second
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        if let PayoutTable::TopTwo { first_bps, second_bps } = self {
+            let total = *first_bps as u64 + *second_bps as u64;
+            if total != BASIS_POINTS_DENOMINATOR || first_bps < second_bps {
+                return Err(RpsError::InvalidPayoutTable.into());
+            }
+        }
+        Ok(())
+    }
+
+    // Amount paid to each seat out of `pot`. Seats with no standing are out of
+    // the running and get nothing.
+    pub fn split(&self, pot: u64, standings: &[Option<u8>]) -> Vec<u64> {
+        let mut shares = vec![0u64; standings.len()];
+
+        // Highest score first; a stable sort keeps equal scores together
+        let mut ranked: Vec<(usize, u8)> = standings
+            .iter()
+            .enumerate()
+            .filter_map(|(seat, standing)| standing.map(|score| (seat, score)))
+            .collect();
+        ranked.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+        if ranked.is_empty() {
+            return shares;
+        }
+
+        let mut place_bps: Vec<u64> = match self {
+            PayoutTable::WinnerTakeAll => vec![BASIS_POINTS_DENOMINATOR],
+            PayoutTable::TopTwo { first_bps, second_bps } => vec![*first_bps as u64, *second_bps as u64],
+            PayoutTable::ProportionalToScore => {
+                let total: u64 = ranked.iter().map(|(_, score)| *score as u64).sum();
+                if total > 0 {
+                    for (seat, score) in &ranked {
+                        shares[*seat] = (pot as u128 * *score as u128 / total as u128) as u64;
+                    }
+                    return shares;
+                }
+
+                // Nobody scored: everyone shares first place
+                vec![BASIS_POINTS_DENOMINATOR]
+            }
+        };
+
+        // Places nobody reached go to first place
+        if ranked.len() < place_bps.len() {
+            let unclaimed: u64 = place_bps.drain(ranked.len()..).sum();
+            place_bps[0] += unclaimed;
+        }
+
+        // Every group of tied players pools the places it covers and splits them evenly
+        let mut place = 0;
+        while place < ranked.len() {
+            let score = ranked[place].1;
+            let tied = ranked[place..].iter().take_while(|(_, s)| *s == score).count();
+
+            let bps: u64 = place_bps.iter().skip(place).take(tied).sum();
+            let group_total = (pot as u128 * bps as u128 / BASIS_POINTS_DENOMINATOR as u128) as u64;
+            for (seat, _) in &ranked[place..place + tied] {
+                shares[*seat] = group_total / tied as u64;
+            }
+
+            place += tied;
+        }
+
+        shares
+    }
+}
@@ -0,0 +1,260 @@
+// Per-wallet lifetime stats, kept in a PDA of the wallet so leaderboards can be
+// built from on-chain state. Profiles are passed after the fixed accounts of
+// RevealChoice and ResolveTimeout: a reveal counts the revealed choice in the
+// revealer's profile, and the instruction that settles a game records the game
+// in the profile of every human player, including a new skill rating. Settling
+// needs the profile address of every human seat; an address where no profile
+// was created stands for a wallet without one, so no profile can be left out.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+use crate::{
+    error::RpsError,
+    rating::{apply_change, rating_changes, INITIAL_RATING},
+    rules::MAX_CHOICES,
+    Choice, Game, Player, GAME_RESERVED_SPACE,
+};
+
+// Seed prefix of player profile PDAs
+pub const PROFILE_SEED: &[u8] = b"profile";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct PlayerProfile {
+    pub wallet: Pubkey,
+    pub games_played: u64,
+    pub rounds_won: u64,                   // Rounds in which the player scored
+    pub choice_counts: [u64; MAX_CHOICES], // Reveals of each choice, indexed by choice - 1
+    pub total_wagered: u64,                // Entry fees paid into settled games
+    pub total_won: u64,                    // Payouts frozen at settlement, after fees
+    pub bump: u8,                          // Bump seed of the profile PDA
+    pub rating: u16,                       // Skill rating, see rating.rs and current_rating
+}
+
+impl PlayerProfile {
+    pub const LEN: usize = 32 + // wallet
+        8 +  // games_played
+        8 +  // rounds_won
+        8 * MAX_CHOICES + // choice_counts
+        8 +  // total_wagered
+        8 +  // total_won
+        1 +  // bump
+        2;   // rating
+
+    // Space allocated for a profile; like games, profiles keep a zeroed reserve
+    // for fields appended later
+    pub fn space() -> usize {
+        Self::LEN + GAME_RESERVED_SPACE
+    }
+
+    // Load a profile, ensuring the account is the profile PDA of its wallet
+    pub fn load(program_id: &Pubkey, profile_account: &AccountInfo) -> Result<Self, ProgramError> {
+        if profile_account.owner != program_id {
+            return Err(RpsError::InvalidProfileAccount.into());
+        }
+
+        let profile = Self::deserialize(&mut &profile_account.data.borrow()[..])
+            .map_err(|_| RpsError::InvalidProfileAccount)?;
+
+        let expected = Pubkey::create_program_address(
+            &[PROFILE_SEED, profile.wallet.as_ref(), &[profile.bump]],
+            program_id,
+        )
+        .map_err(|_| RpsError::InvalidProfileAccount)?;
+        if expected != *profile_account.key {
+            return Err(RpsError::InvalidProfileAccount.into());
+        }
+
+        Ok(profile)
+    }
+
+    pub fn save(&self, profile_account: &AccountInfo) -> ProgramResult {
+        self.serialize(&mut &mut profile_account.data.borrow_mut()[..])?;
+        Ok(())
+    }
+
+    // Profiles created before ratings existed decode a zero rating
+    pub fn current_rating(&self) -> u16 {
+        if self.rating == 0 {
+            INITIAL_RATING
+        } else {
+            self.rating
+        }
+    }
+}
+
+// Derive the profile PDA of a wallet
+pub fn find_profile_address(wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROFILE_SEED, wallet.as_ref()], program_id)
+}
+
+// Implementation for creating a wallet's profile; the wallet pays its rent
+pub fn process_create_player_profile(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let wallet = next_account_info(accounts_iter)?;
+    let profile_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // Ensure the wallet signed the transaction
+    if !wallet.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (profile_key, bump) = find_profile_address(wallet.key, program_id);
+    if profile_key != *profile_account.key {
+        return Err(RpsError::InvalidProfileAccount.into());
+    }
+
+    let space = PlayerProfile::space();
+    let rent = Rent::get()?;
+    invoke_signed(
+        &system_instruction::create_account(
+            wallet.key,
+            profile_account.key,
+            rent.minimum_balance(space),
+            space as u64,
+            program_id,
+        ),
+        &[wallet.clone(), profile_account.clone(), system_program.clone()],
+        &[&[PROFILE_SEED, wallet.key.as_ref(), &[bump]]],
+    )?;
+
+    let profile = PlayerProfile {
+        wallet: *wallet.key,
+        games_played: 0,
+        rounds_won: 0,
+        choice_counts: [0; MAX_CHOICES],
+        total_wagered: 0,
+        total_won: 0,
+        bump,
+        rating: INITIAL_RATING,
+    };
+    profile.save(profile_account)?;
+
+    msg!("Player profile created for {}", wallet.key);
+
+    Ok(())
+}
+
+// Profiles passed to a game instruction, each belonging to a human player of the game
+pub(crate) struct Profiles<'a, 'b> {
+    entries: Vec<(&'a AccountInfo<'b>, PlayerProfile)>,
+    without_profile: Vec<Pubkey>, // Players whose profile address was passed but never created
+}
+
+impl<'a, 'b> Profiles<'a, 'b> {
+    pub(crate) fn load(
+        program_id: &Pubkey,
+        game: &Game,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let mut entries: Vec<(&'a AccountInfo<'b>, PlayerProfile)> = Vec::with_capacity(accounts.len());
+        let mut without_profile = Vec::new();
+
+        for account in accounts {
+            if *account.owner == system_program::id() && account.data_is_empty() {
+                let wallet = game
+                    .players
+                    .iter()
+                    .find(|p| !p.is_bot && find_profile_address(&p.pubkey, program_id).0 == *account.key)
+                    .map(|p| p.pubkey)
+                    .ok_or(RpsError::InvalidProfileAccount)?;
+                if without_profile.contains(&wallet) {
+                    return Err(RpsError::DuplicateProfile.into());
+                }
+                without_profile.push(wallet);
+                continue;
+            }
+
+            let profile = PlayerProfile::load(program_id, account)?;
+
+            if !game.players.iter().any(|p| !p.is_bot && p.pubkey == profile.wallet) {
+                return Err(RpsError::PlayerNotInGame.into());
+            }
+
+            // A profile passed twice would be counted twice
+            if entries.iter().any(|(_, seen)| seen.wallet == profile.wallet) {
+                return Err(RpsError::DuplicateProfile.into());
+            }
+
+            entries.push((account, profile));
+        }
+
+        Ok(Profiles { entries, without_profile })
+    }
+
+    // Count a revealed choice in the revealer's profile, if passed
+    pub(crate) fn record_choice(&mut self, wallet: &Pubkey, choice: &Choice) {
+        if choice.index() == 0 {
+            return;
+        }
+
+        if let Some((_, profile)) = self.entries.iter_mut().find(|(_, p)| p.wallet == *wallet) {
+            let count = &mut profile.choice_counts[choice.index() as usize - 1];
+            *count = count.saturating_add(1);
+        }
+    }
+
+    fn find(&self, wallet: &Pubkey) -> Option<&PlayerProfile> {
+        self.entries.iter().map(|(_, p)| p).find(|p| p.wallet == *wallet)
+    }
+
+    // Record a game that just settled, then start the next game's wager tally.
+    // Fails unless every human seat's profile address was passed.
+    pub(crate) fn record_settlement(&mut self, game: &mut Game) -> ProgramResult {
+        let accounted = |wallet: &Pubkey| self.find(wallet).is_some() || self.without_profile.contains(wallet);
+        if !game.players.iter().filter(|p| !p.is_bot).all(|p| accounted(&p.pubkey)) {
+            return Err(RpsError::MissingProfile.into());
+        }
+
+        // Humans are rated against each other by final score, players knocked
+        // out of a battle royale ranking below everyone still standing. Players
+        // without a profile count at the initial rating.
+        let humans: Vec<&Player> = game.players.iter().filter(|p| !p.is_bot).collect();
+        let ratings: Vec<u16> = humans
+            .iter()
+            .map(|p| self.find(&p.pubkey).map_or(INITIAL_RATING, |profile| profile.current_rating()))
+            .collect();
+        let standings: Vec<(bool, u8)> = humans.iter().map(|p| (!p.eliminated, p.score)).collect();
+        let changes = rating_changes(&ratings, &standings);
+
+        for (_, profile) in &mut self.entries {
+            let Some(seat) = humans.iter().position(|p| p.pubkey == profile.wallet) else {
+                continue;
+            };
+            let player = humans[seat];
+
+            profile.games_played = profile.games_played.saturating_add(1);
+            profile.rounds_won = profile.rounds_won.saturating_add(player.rounds_won as u64);
+            profile.total_wagered = profile.total_wagered.saturating_add(player.wagered);
+            profile.total_won = profile.total_won.saturating_add(player.winnings);
+            profile.rating = apply_change(ratings[seat], changes[seat]);
+        }
+
+        for player in &mut game.players {
+            player.wagered = 0;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn save(&self) -> ProgramResult {
+        for (account, profile) in &self.entries {
+            profile.save(account)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+// Randomness for every random decision a game makes (player count, bot
+// choices, tie-breaks).
+//
+// Each game keeps an entropy pool that absorbs the game and player keys and
+// every revealed salt. Salts are committed before they are revealed, so no
+// player can steer the pool once they have seen the others. No draw reads the
+// SlotHashes sysvar at the time it is made, since whoever sends the drawing
+// transaction could wait for a slot they like.
+//
+// Bot moves are drawn from the pool alone. The pool takes the newest slot hash
+// when the commit phase closes, before any human reveals, and then absorbs the
+// committed salts as they are revealed. The last human to reveal, or whoever
+// resolves the reveal timeout, therefore cannot wait for a slot that makes the
+// bots lose. Bracket tie-breaks likewise draw from the finished match's pool.
+//
+// The player count of a lobby with a range of sizes is drawn from the pool and
+// a seed the host commits to at creation and reveals once the minimum is
+// seated. Joiners cannot predict the seed, so the player completing the
+// minimum cannot grind their key or pick a slot to steer the count; the host
+// could, but they could as well have fixed the count. Seats past the minimum
+// only open once the count is drawn, and the host has one reveal timeout to
+// reveal; a lobby whose seed is held back can only be cancelled. The count is
+// drawn once: later games with the same players keep it.
+
+use solana_program::{
+    account_info::AccountInfo,
+    hash::hashv,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::slot_hashes,
+};
+
+use crate::error::RpsError;
+
+// Domain tag for seeds derived from a game's entropy pool
+pub const RANDOMNESS_DOMAIN: &[u8] = b"rps-randomness";
+
+// Domain tag of the host's player count seed commitment
+pub const COUNT_SEED_DOMAIN: &[u8] = b"rps-count-seed";
+
+// Absorb `data` into an entropy pool
+pub fn mix_entropy(pool: &mut [u8; 32], data: &[&[u8]]) {
+    let mut parts: Vec<&[u8]> = vec![pool.as_ref()];
+    parts.extend_from_slice(data);
+    *pool = hashv(&parts).to_bytes();
+}
+
+// Hash of the most recent slot recorded in the SlotHashes sysvar.
+// The sysvar is too large to deserialize on-chain, so the first entry is read
+// directly: a u64 entry count followed by (slot: u64, hash: [u8; 32]) pairs,
+// newest first.
+pub fn latest_slot_hash(slot_hashes: &AccountInfo) -> Result<[u8; 32], ProgramError> {
+    if !slot_hashes::check_id(slot_hashes.key) {
+        return Err(RpsError::InvalidSlotHashes.into());
+    }
+
+    let data = slot_hashes.try_borrow_data()?;
+    if data.len() < 48 || data[0..8] == [0; 8] {
+        return Err(RpsError::InvalidSlotHashes.into());
+    }
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&data[16..48]);
+    Ok(hash)
+}
+
+// Hash the host commits to when creating a lobby with a range of sizes
+pub fn count_seed_hash(game: &Pubkey, seed: &[u8; 32]) -> [u8; 32] {
+    hashv(&[COUNT_SEED_DOMAIN, game.as_ref(), seed]).to_bytes()
+}
+
+// Deterministic stream of random numbers for one draw
+pub struct GameRng {
+    seed: [u8; 32],
+    counter: u64,
+}
+
+impl GameRng {
+    pub fn new(pool: &[u8; 32], slot_hash: &[u8; 32]) -> Self {
+        GameRng {
+            seed: hashv(&[RANDOMNESS_DOMAIN, pool, slot_hash]).to_bytes(),
+            counter: 0,
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let output = hashv(&[&self.seed, &self.counter.to_le_bytes()]).to_bytes();
+        self.counter += 1;
+
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&output[..8]);
+        u64::from_le_bytes(bytes)
+    }
+
+    // Uniform value in `0..bound`; the modulo bias is negligible for the small
+    // bounds used by the game
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
@@ -0,0 +1,82 @@
+// Multi-player Elo ratings in integer math. Every player is scored against
+// every other as in a two-player Elo game (win, draw or loss by final
+// ranking), and the K factor is shared out over the opponents faced.
+// Expected scores come from a fixed table of the logistic curve.
+
+// Rating of a profile that has not played a rated game
+pub const INITIAL_RATING: u16 = 1500;
+
+// Ratings never drop below this floor
+pub const MIN_RATING: u16 = 100;
+
+// Most a player's rating can move in one game
+pub const K_FACTOR: i64 = 32;
+
+// Fixed-point scale of expected scores
+pub const SCORE_SCALE: i64 = 1_000_000;
+
+// Rating gap between entries of EXPECTED_SCORES
+const EXPECTED_STEP: i64 = 25;
+
+// Expected score of a player rated `i * EXPECTED_STEP` points above their
+// opponent: SCORE_SCALE / (1 + 10^(-gap / 400)), rounded
+const EXPECTED_SCORES: [i64; 33] = [
+    500_000, 535_916, 571_463, 606_288, 640_065, 672_510, 703_385, 732_507, 759_747, 785_027,
+    808_318, 829_633, 849_020, 866_557, 882_338, 896_477, 909_091, 920_305, 930_242, 939_022,
+    946_760, 953_565, 959_537, 964_769, 969_347, 973_346, 976_836, 979_878, 982_528, 984_834,
+    986_840, 988_584, 990_099,
+];
+
+// Expected score against an opponent `gap` points lower rated (negative when
+// higher rated), interpolated between table entries and flat beyond the table
+pub fn expected_score(gap: i64) -> i64 {
+    if gap < 0 {
+        return SCORE_SCALE - expected_score(-gap);
+    }
+
+    let last = EXPECTED_SCORES.len() - 1;
+    let index = (gap / EXPECTED_STEP) as usize;
+    if index >= last {
+        return EXPECTED_SCORES[last];
+    }
+
+    let low = EXPECTED_SCORES[index];
+    let high = EXPECTED_SCORES[index + 1];
+    low + (high - low) * (gap % EXPECTED_STEP) / EXPECTED_STEP
+}
+
+// Rating change of each player given their current ratings and their final
+// standings, where a higher standing finished ahead and equal standings drew.
+// Changes are rounded to the nearest point, halves away from zero.
+pub fn rating_changes<S: Ord>(ratings: &[u16], standings: &[S]) -> Vec<i32> {
+    let players = ratings.len();
+    if players < 2 {
+        return vec![0; players];
+    }
+
+    (0..players)
+        .map(|i| {
+            let surplus: i64 = (0..players)
+                .filter(|j| *j != i)
+                .map(|j| {
+                    let actual = match standings[i].cmp(&standings[j]) {
+                        std::cmp::Ordering::Greater => SCORE_SCALE,
+                        std::cmp::Ordering::Equal => SCORE_SCALE / 2,
+                        std::cmp::Ordering::Less => 0,
+                    };
+                    actual - expected_score(ratings[i] as i64 - ratings[j] as i64)
+                })
+                .sum();
+
+            let numerator = K_FACTOR * surplus;
+            let denominator = (players as i64 - 1) * SCORE_SCALE;
+            let rounding = numerator.signum() * denominator / 2;
+            ((numerator + rounding) / denominator) as i32
+        })
+        .collect()
+}
+
+// Apply a rating change, keeping the result between MIN_RATING and u16::MAX
+pub fn apply_change(rating: u16, change: i32) -> u16 {
+    (rating as i32 + change).clamp(MIN_RATING as i32, u16::MAX as i32) as u16
+}
@@ -0,0 +1,89 @@
+// Rule sets deciding which choice beats which. A rule set is a dominance
+// tournament over an odd number of choices: every pair of distinct choices
+// has exactly one winner and every choice beats exactly half of the others,
+// so no choice is stronger than another.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::program_error::ProgramError;
+
+use crate::{error::RpsError, Choice};
+
+// Largest number of choices a custom rule set may have; masks are u16
+pub const MAX_CHOICES: usize = 15;
+
+// Rock, Paper, Scissors
+const CLASSIC: [u16; 3] = [0b100, 0b001, 0b010];
+
+// Rock, Paper, Scissors, Lizard, Spock
+const LIZARD_SPOCK: [u16; 5] = [0b01100, 0b10001, 0b01010, 0b10010, 0b00101];
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum RuleSet {
+    Classic,
+    LizardSpock,
+    // Bit j of entry i is set when choice i + 1 beats choice j + 1
+    Custom(Vec<u16>),
+}
+
+impl RuleSet {
+    // Serialized size of the largest rule set
+    pub const MAX_LEN: usize = 1 + 4 + MAX_CHOICES * 2;
+
+    fn beat_masks(&self) -> &[u16] {
+        match self {
+            RuleSet::Classic => &CLASSIC,
+            RuleSet::LizardSpock => &LIZARD_SPOCK,
+            RuleSet::Custom(masks) => masks,
+        }
+    }
+
+    pub fn choice_count(&self) -> u8 {
+        self.beat_masks().len() as u8
+    }
+
+    // Whether `choice` can be played under these rules
+    pub fn is_valid_choice(&self, choice: &Choice) -> bool {
+        (1..=self.choice_count()).contains(&choice.index())
+    }
+
+    // Whether `winner` beats `loser`; either being unplayed is never a win
+    pub fn beats(&self, winner: &Choice, loser: &Choice) -> bool {
+        if !self.is_valid_choice(winner) || !self.is_valid_choice(loser) {
+            return false;
+        }
+
+        let mask = self.beat_masks()[winner.index() as usize - 1];
+        mask & (1 << (loser.index() - 1)) != 0
+    }
+
+    // Check that the rules form a balanced dominance tournament
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        let masks = self.beat_masks();
+        let count = masks.len();
+        if !(3..=MAX_CHOICES).contains(&count) || count % 2 == 0 {
+            return Err(RpsError::InvalidRuleSet.into());
+        }
+
+        for (i, mask) in masks.iter().enumerate() {
+            // No choice beats itself, nor anything outside the rule set
+            if mask & (1 << i) != 0 || mask >> count != 0 {
+                return Err(RpsError::InvalidRuleSet.into());
+            }
+
+            if mask.count_ones() as usize != count / 2 {
+                return Err(RpsError::InvalidRuleSet.into());
+            }
+
+            // Exactly one of every pair wins
+            for (j, other) in masks.iter().enumerate().skip(i + 1) {
+                let i_beats_j = mask & (1 << j) != 0;
+                let j_beats_i = other & (1 << i) != 0;
+                if i_beats_j == j_beats_i {
+                    return Err(RpsError::InvalidRuleSet.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
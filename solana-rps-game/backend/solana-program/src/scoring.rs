@@ -0,0 +1,118 @@
+// Scoring models turning one round of choices into points. Seats that did not
+// reveal hold `Choice::NONE` and lose to any revealed choice; seats that are
+// out of the game are passed as `None` and never score.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{rules::RuleSet, Choice};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum ScoringModel {
+    // One point per opponent beaten
+    Pairwise,
+    // One point to everyone holding the winning choice, but only when exactly
+    // two distinct choices were revealed
+    RoundWinners,
+    // One point to everyone who avoided the single most common choice
+    MajorityLoses,
+}
+
+impl ScoringModel {
+    // Points earned by each seat this round
+    pub fn round_points(&self, rules: &RuleSet, choices: &[Option<Choice>]) -> Vec<u8> {
+        match self {
+            ScoringModel::Pairwise => pairwise_points(rules, choices),
+            ScoringModel::RoundWinners => round_winner_points(rules, choices),
+            ScoringModel::MajorityLoses => majority_loses_points(choices),
+        }
+    }
+}
+
+fn pairwise_points(rules: &RuleSet, choices: &[Option<Choice>]) -> Vec<u8> {
+    let mut points = vec![0u8; choices.len()];
+
+    for i in 0..choices.len() {
+        for j in (i + 1)..choices.len() {
+            let (Some(choice_i), Some(choice_j)) = (choices[i], choices[j]) else {
+                continue;
+            };
+
+            match (choice_i, choice_j) {
+                (Choice::NONE, Choice::NONE) => {
+                    // Neither player revealed - no points awarded
+                },
+                (_, Choice::NONE) => {
+                    // Player j never revealed and takes the default loss
+                    points[i] += 1;
+                },
+                (Choice::NONE, _) => {
+                    // Player i never revealed and takes the default loss
+                    points[j] += 1;
+                },
+                _ if rules.beats(&choice_i, &choice_j) => {
+                    points[i] += 1;
+                },
+                _ if rules.beats(&choice_j, &choice_i) => {
+                    points[j] += 1;
+                },
+                _ => {
+                    // Tie - no points awarded
+                }
+            }
+        }
+    }
+
+    points
+}
+
+// Distinct revealed choices, in seat order
+fn distinct_choices(choices: &[Option<Choice>]) -> Vec<Choice> {
+    let mut distinct = Vec::new();
+    for choice in choices.iter().flatten() {
+        if *choice != Choice::NONE && !distinct.contains(choice) {
+            distinct.push(*choice);
+        }
+    }
+    distinct
+}
+
+fn round_winner_points(rules: &RuleSet, choices: &[Option<Choice>]) -> Vec<u8> {
+    let distinct = distinct_choices(choices);
+    let unrevealed = choices.contains(&Some(Choice::NONE));
+
+    // With a single revealed choice it only wins against players who never revealed
+    let winner = match distinct[..] {
+        [a, b] if rules.beats(&a, &b) => Some(a),
+        [a, b] if rules.beats(&b, &a) => Some(b),
+        [a] if unrevealed => Some(a),
+        _ => None,
+    };
+
+    choices
+        .iter()
+        .map(|choice| (winner.is_some() && *choice == winner) as u8)
+        .collect()
+}
+
+fn majority_loses_points(choices: &[Option<Choice>]) -> Vec<u8> {
+    let counts: Vec<(Choice, usize)> = distinct_choices(choices)
+        .into_iter()
+        .map(|d| (d, choices.iter().filter(|c| **c == Some(d)).count()))
+        .collect();
+
+    // Nobody scores unless one choice was picked strictly more often than every other
+    let most = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    let mut leaders = counts.iter().filter(|(_, count)| *count == most);
+    let majority = match (leaders.next(), leaders.next()) {
+        (Some((choice, _)), None) if most > 1 => *choice,
+        _ => return vec![0; choices.len()],
+    };
+
+    choices
+        .iter()
+        .map(|choice| match choice {
+            Some(choice) => (*choice != Choice::NONE && *choice != majority) as u8,
+            None => 0,
+        })
+        .collect()
+}
@@ -0,0 +1,694 @@
+// Single-elimination tournaments.
+//
+// Entrants pay one buy-in into the tournament's vault. Each bracket match is
+// an ordinary two-player `Game` account at a PDA of the tournament, played
+// with the usual CommitChoice/RevealChoice/ResolveTimeout instructions. Once a
+// match is finished its result is recorded with AdvanceTournament, which
+// closes the match and, when a bracket round is complete, moves the winners on.
+// The prize pool is split by finishing place when the final is recorded,
+// after the protocol fee snapshotted at creation is sent to the treasury.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+use crate::{
+    access::GameAccess,
+    close_account,
+    events::GameEvent,
+    config::{calculate_fee, ProgramConfig, BASIS_POINTS_DENOMINATOR},
+    error::RpsError,
+    payouts::PayoutTable,
+    randomness::{mix_entropy, GameRng},
+    rules::RuleSet,
+    scoring::ScoringModel,
+    transfer_from_vault, transfer_to_vault, AccountType, CurrencyMode, Game, GameMode, GameState, Player,
+    GAME_LAYOUT_VERSION,
+};
+
+// Seed prefix of bracket match PDAs
+pub const MATCH_SEED: &[u8] = b"match";
+
+// Seed prefix of tournament vault PDAs, kept apart from the game vaults
+pub const TOURNAMENT_VAULT_SEED: &[u8] = b"tournament_vault";
+
+// Largest supported bracket
+pub const MAX_ENTRANTS: u8 = 64;
+
+// Bytes kept at the end of every tournament account for fields added later;
+// `Tournament::save` zeroes them, so appended fields read as zero
+pub const TOURNAMENT_RESERVED_SPACE: usize = 64;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum TournamentState {
+    Registration,
+    InProgress,
+    Finished,
+    Cancelled, // Cancelled before the bracket filled; buy-ins are refundable
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Entrant {
+    pub pubkey: Pubkey,
+    pub alive: bool,           // Still in the bracket
+    pub eliminated_round: u8,  // Bracket round the entrant lost in (0-based)
+    pub payout: u64,           // Prize or refund awaiting claim
+    pub claimed: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Tournament {
+    pub account_type: AccountType,
+    pub organizer: Pubkey,
+    pub state: TournamentState,
+    pub max_entrants: u8,          // Power of two
+    pub buy_in: u64,
+    pub prize_pool: u64,           // Buy-ins not yet assigned to an entrant's payout
+    pub match_rounds: u8,          // Rounds played in every match
+    pub commit_timeout: u64,
+    pub reveal_timeout: u64,
+    pub place_bps: Vec<u16>,       // Share of the pool per place: champion, runner-up, semifinal losers, ...
+    pub entrants: Vec<Entrant>,    // Registration order is the seeding
+    pub bracket_round: u8,         // Current bracket round (0-based)
+    pub remaining: Vec<u8>,        // Entrant indices in the current round, paired in order
+    pub matches_opened: u8,        // Matches of the current round created so far
+    pub vault_bump: u8,
+    pub entropy: [u8; 32],         // Seeds match entropy and tie-breaks
+    pub registration_deadline: u64, // Registration closes and anyone may cancel from here on
+    pub fee_basis_points: u16,     // Protocol fee snapshotted from the config at creation
+}
+
+impl Tournament {
+    pub fn space(max_entrants: u8, places: usize) -> usize {
+        1 +  // account_type
+        32 + // organizer
+        1 +  // state
+        1 +  // max_entrants
+        8 +  // buy_in
+        8 +  // prize_pool
+        1 +  // match_rounds
+        8 +  // commit_timeout
+        8 +  // reveal_timeout
+        4 + places * 2 + // place_bps
+        4 + max_entrants as usize * (32 + 1 + 1 + 8 + 1) + // entrants
+        1 +  // bracket_round
+        4 + max_entrants as usize + // remaining
+        1 +  // matches_opened
+        1 +  // vault_bump
+        32 + // entropy
+        8 +  // registration_deadline
+        2 +  // fee_basis_points
+        TOURNAMENT_RESERVED_SPACE
+    }
+
+    pub fn load(program_id: &Pubkey, account: &AccountInfo) -> Result<Self, ProgramError> {
+        if account.owner != program_id {
+            return Err(RpsError::InvalidTournamentAccount.into());
+        }
+
+        let data = account.data.borrow();
+        if !AccountType::Tournament.tags(&data) {
+            return Err(RpsError::InvalidTournamentAccount.into());
+        }
+        Self::deserialize(&mut &data[..]).map_err(|_| RpsError::InvalidTournamentAccount.into())
+    }
+
+    // Save the tournament, zeroing whatever a longer earlier save left behind it
+    pub fn save(&self, account: &AccountInfo) -> ProgramResult {
+        let mut data = account.data.borrow_mut();
+        let mut tail: &mut [u8] = &mut data[..];
+        self.serialize(&mut tail)?;
+        tail.fill(0);
+        Ok(())
+    }
+
+    pub fn check_vault(&self, program_id: &Pubkey, tournament_key: &Pubkey, vault: &AccountInfo) -> ProgramResult {
+        let expected = Pubkey::create_program_address(
+            &[TOURNAMENT_VAULT_SEED, tournament_key.as_ref(), &[self.vault_bump]],
+            program_id,
+        )
+        .map_err(|_| RpsError::InvalidVault)?;
+
+        if expected != *vault.key {
+            return Err(RpsError::InvalidVault.into());
+        }
+        Ok(())
+    }
+
+    // Number of bracket rounds, e.g. 3 for eight entrants
+    pub fn total_bracket_rounds(&self) -> u8 {
+        self.max_entrants.trailing_zeros() as u8
+    }
+
+    // Entrant indices playing match `index` of the current round
+    pub fn match_pair(&self, index: u8) -> Option<(u8, u8)> {
+        let first = *self.remaining.get(index as usize * 2)?;
+        let second = *self.remaining.get(index as usize * 2 + 1)?;
+        Some((first, second))
+    }
+
+    // Split the pool by finishing place. Tier 0 is the champion, tier t > 0
+    // holds the 2^(t-1) entrants knocked out in bracket round R - t. Every
+    // tier splits its share equally; rounding dust goes to the champion.
+    fn distribute_prizes(&mut self, champion: u8) {
+        let pool = self.prize_pool;
+        let total_rounds = self.total_bracket_rounds();
+        let mut assigned = 0;
+
+        for tier in 1..self.place_bps.len() {
+            let tier_total = (pool as u128 * self.place_bps[tier] as u128
+                / BASIS_POINTS_DENOMINATOR as u128) as u64;
+            let eliminated_round = total_rounds - tier as u8;
+            let members = 1u64 << (tier - 1);
+            let share = tier_total / members;
+
+            for entrant in self.entrants.iter_mut() {
+                if !entrant.alive && entrant.eliminated_round == eliminated_round {
+                    entrant.payout += share;
+                    assigned += share;
+                }
+            }
+        }
+
+        self.entrants[champion as usize].payout += pool - assigned;
+        self.prize_pool = 0;
+    }
+}
+
+// Derive the vault PDA that escrows a tournament's buy-ins
+pub fn find_tournament_vault_address(tournament: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TOURNAMENT_VAULT_SEED, tournament.as_ref()], program_id)
+}
+
+// Derive the match account for `index` in bracket round `round`
+pub fn find_match_address(tournament: &Pubkey, round: u8, index: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MATCH_SEED, tournament.as_ref(), &[round], &[index]], program_id)
+}
+
+fn validate_place_bps(max_entrants: u8, place_bps: &[u16]) -> ProgramResult {
+    let places = max_entrants.trailing_zeros() as usize + 1;
+    let total: u64 = place_bps.iter().map(|bps| *bps as u64).sum();
+    if place_bps.is_empty() || place_bps.len() > places || total != BASIS_POINTS_DENOMINATOR {
+        return Err(RpsError::InvalidPrizeTable.into());
+    }
+    Ok(())
+}
+
+// Implementation for creating a tournament; registration opens immediately
+#[allow(clippy::too_many_arguments)]
+pub fn process_create_tournament(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_entrants: u8,
+    buy_in: u64,
+    match_rounds: u8,
+    commit_timeout_seconds: u64,
+    reveal_timeout_seconds: u64,
+    place_bps: Vec<u16>,
+    registration_timeout_seconds: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let organizer = next_account_info(accounts_iter)?;
+    let tournament_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+
+    // Ensure the organizer signed the transaction
+    if !organizer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if !max_entrants.is_power_of_two() || !(2..=MAX_ENTRANTS).contains(&max_entrants) {
+        return Err(RpsError::InvalidBracketSize.into());
+    }
+
+    if match_rounds == 0 {
+        return Err(RpsError::InvalidTotalRounds.into());
+    }
+
+    if commit_timeout_seconds == 0 || reveal_timeout_seconds == 0 {
+        return Err(RpsError::InvalidPhaseTimeout.into());
+    }
+
+    if registration_timeout_seconds == 0 {
+        return Err(RpsError::InvalidRegistrationTimeout.into());
+    }
+
+    validate_place_bps(max_entrants, &place_bps)?;
+
+    let config = ProgramConfig::load(program_id, config_account)?;
+
+    let space = Tournament::space(max_entrants, place_bps.len());
+    let rent = Rent::get()?;
+    invoke(
+        &system_instruction::create_account(
+            organizer.key,
+            tournament_account.key,
+            rent.minimum_balance(space),
+            space as u64,
+            program_id,
+        ),
+        &[organizer.clone(), tournament_account.clone(), system_program.clone()],
+    )?;
+
+    // Buy-ins are escrowed in a vault PDA like a game's, under a seed of its own
+    let (vault_key, vault_bump) = find_tournament_vault_address(tournament_account.key, program_id);
+    if vault_key != *vault.key {
+        return Err(RpsError::InvalidVault.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            organizer.key,
+            vault.key,
+            rent.minimum_balance(0),
+            0,
+            program_id,
+        ),
+        &[organizer.clone(), vault.clone(), system_program.clone()],
+        &[&[TOURNAMENT_VAULT_SEED, tournament_account.key.as_ref(), &[vault_bump]]],
+    )?;
+
+    let mut entropy = [0u8; 32];
+    mix_entropy(&mut entropy, &[tournament_account.key.as_ref(), organizer.key.as_ref()]);
+
+    let tournament = Tournament {
+        account_type: AccountType::Tournament,
+        organizer: *organizer.key,
+        state: TournamentState::Registration,
+        max_entrants,
+        buy_in,
+        prize_pool: 0,
+        match_rounds,
+        commit_timeout: commit_timeout_seconds,
+        reveal_timeout: reveal_timeout_seconds,
+        place_bps,
+        entrants: Vec::new(),
+        bracket_round: 0,
+        remaining: Vec::new(),
+        matches_opened: 0,
+        vault_bump,
+        entropy,
+        registration_deadline: (Clock::get()?.unix_timestamp as u64).saturating_add(registration_timeout_seconds),
+        fee_basis_points: config.fee_basis_points,
+    };
+    tournament.save(tournament_account)?;
+
+    msg!("Tournament created for {} entrants", max_entrants);
+
+    Ok(())
+}
+
+// Implementation for registering in a tournament; the bracket starts once full
+pub fn process_register_for_tournament(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let entrant = next_account_info(accounts_iter)?;
+    let tournament_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // Ensure the entrant signed the transaction
+    if !entrant.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut tournament = Tournament::load(program_id, tournament_account)?;
+    tournament.check_vault(program_id, tournament_account.key, vault)?;
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    if tournament.state != TournamentState::Registration || now >= tournament.registration_deadline {
+        return Err(RpsError::TournamentNotOpen.into());
+    }
+
+    if tournament.entrants.iter().any(|e| e.pubkey == *entrant.key) {
+        return Err(RpsError::PlayerAlreadyJoined.into());
+    }
+
+    tournament.entrants.push(Entrant {
+        pubkey: *entrant.key,
+        alive: true,
+        eliminated_round: 0,
+        payout: 0,
+        claimed: false,
+    });
+    tournament.prize_pool += tournament.buy_in;
+    mix_entropy(&mut tournament.entropy, &[entrant.key.as_ref()]);
+
+    // Seed the bracket in registration order once every slot is taken
+    if tournament.entrants.len() == tournament.max_entrants as usize {
+        tournament.state = TournamentState::InProgress;
+        tournament.remaining = (0..tournament.max_entrants).collect();
+        msg!("Tournament bracket is full");
+    }
+
+    tournament.save(tournament_account)?;
+
+    if tournament.buy_in > 0 {
+        transfer_to_vault(entrant, vault, system_program, tournament.buy_in)?;
+    }
+
+    msg!("Entrant registered: {}", entrant.key);
+
+    Ok(())
+}
+
+// Implementation for opening the next match of the current bracket round.
+// The payer funds the match account's rent and gets it back when the result is recorded.
+pub fn process_open_tournament_match(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let payer = next_account_info(accounts_iter)?;
+    let tournament_account = next_account_info(accounts_iter)?;
+    let match_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // Ensure the payer signed the transaction
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut tournament = Tournament::load(program_id, tournament_account)?;
+
+    if tournament.state != TournamentState::InProgress {
+        return Err(RpsError::TournamentNotInProgress.into());
+    }
+
+    let index = tournament.matches_opened;
+    let (first, second) = tournament.match_pair(index).ok_or(RpsError::NoMatchToOpen)?;
+
+    let (match_key, match_bump) =
+        find_match_address(tournament_account.key, tournament.bracket_round, index, program_id);
+    if match_key != *match_account.key {
+        return Err(RpsError::InvalidMatchAccount.into());
+    }
+
+    let space = Game::space(2);
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            match_account.key,
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            program_id,
+        ),
+        &[payer.clone(), match_account.clone(), system_program.clone()],
+        &[&[
+            MATCH_SEED,
+            tournament_account.key.as_ref(),
+            &[tournament.bracket_round],
+            &[index],
+            &[match_bump],
+        ]],
+    )?;
+
+    // Matches hold no funds: the prize pool stays in the tournament vault
+    let clock = Clock::get()?;
+    let mut entropy = tournament.entropy;
+    mix_entropy(&mut entropy, &[match_account.key.as_ref()]);
+
+    let mut game = Game {
+        account_type: AccountType::Game,
+        layout_version: GAME_LAYOUT_VERSION,
+        host: *payer.key,
+        players: vec![
+            Player::new(tournament.entrants[first as usize].pubkey),
+            Player::new(tournament.entrants[second as usize].pubkey),
+        ],
+        min_players: 2,
+        max_players: 2,
+        state: GameState::WaitingForPlayers,
+        current_round: 1,
+        total_rounds: tournament.match_rounds,
+        entry_fee: 0,
+        game_pot: 0,
+        required_timeout: tournament.commit_timeout,
+        last_action_timestamp: clock.unix_timestamp as u64,
+        player_count: 2,
+        losers_can_rejoin: false,
+        game_mode: GameMode::Manual,
+        auto_round_delay: 0,
+        max_auto_rounds: 0,
+        current_auto_round: 0,
+        currency_mode: CurrencyMode::SOL,
+        vault_bump: 0,
+        token_mint: Pubkey::default(),
+        fee_basis_points: 0,
+        fees_owed: 0,
+        commit_timeout: tournament.commit_timeout,
+        reveal_timeout: tournament.reveal_timeout,
+        commit_deadline: 0,
+        reveal_deadline: 0,
+        entropy,
+        tournament: *tournament_account.key,
+        survivors: 0,
+        opponent: Pubkey::default(),
+        rule_set: RuleSet::Classic,
+        scoring_model: ScoringModel::Pairwise,
+        payout_table: PayoutTable::WinnerTakeAll,
+        min_rating: 0,
+        max_rating: 0,
+        access: GameAccess::Open,
+        count_seed_hash: [0; 32],
+        game_expiry_seconds: 0, // Bracket matches are closed when their result is recorded
+        count_seed_deadline: 0,
+    };
+    game.begin_commit_phase(clock.unix_timestamp as u64);
+    game.save(match_account)?;
+
+    GameEvent::game_created(match_account.key, &game).emit()?;
+    for player in &game.players {
+        GameEvent::player_joined(match_account.key, player).emit()?;
+    }
+
+    tournament.matches_opened += 1;
+    tournament.save(tournament_account)?;
+
+    msg!("Opened bracket round {} match {}", tournament.bracket_round, index);
+
+    Ok(())
+}
+
+// Implementation for recording a finished match. The loser is eliminated, the
+// match account is closed back to whoever opened it, and the next bracket
+// round is seeded once every match of the current one is recorded.
+pub fn process_advance_tournament(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let caller = next_account_info(accounts_iter)?;
+    let tournament_account = next_account_info(accounts_iter)?;
+    let match_account = next_account_info(accounts_iter)?;
+    let match_host = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+
+    // Ensure the caller signed the transaction
+    if !caller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut tournament = Tournament::load(program_id, tournament_account)?;
+    tournament.check_vault(program_id, tournament_account.key, vault)?;
+
+    let config = ProgramConfig::load(program_id, config_account)?;
+    if *treasury.key != config.treasury {
+        return Err(RpsError::InvalidTreasury.into());
+    }
+
+    if tournament.state != TournamentState::InProgress {
+        return Err(RpsError::TournamentNotInProgress.into());
+    }
+
+    let game = Game::load(program_id, match_account)?;
+    if game.tournament != *tournament_account.key {
+        return Err(RpsError::InvalidMatchAccount.into());
+    }
+
+    // The match must be one of the current round's PDAs
+    let index = (0..tournament.matches_opened)
+        .find(|i| {
+            find_match_address(tournament_account.key, tournament.bracket_round, *i, program_id).0
+                == *match_account.key
+        })
+        .ok_or(RpsError::InvalidMatchAccount)?;
+    let (first, second) = tournament.match_pair(index).ok_or(RpsError::InvalidMatchAccount)?;
+
+    if game.state != GameState::Finished {
+        return Err(RpsError::MatchNotFinished.into());
+    }
+
+    if *match_host.key != game.host {
+        return Err(RpsError::InvalidHost.into());
+    }
+
+    // A player who let the commit phase run out forfeits; otherwise the
+    // higher score wins. A tie is broken by a draw from the match's entropy
+    // pool, which holds both revealed salts and stops changing once the match
+    // is finished, so the draw cannot depend on when the result is recorded.
+    let score_of = |entrant: u8| {
+        let pubkey = tournament.entrants[entrant as usize].pubkey;
+        game.players.iter().find(|p| p.pubkey == pubkey && !p.eliminated).map(|p| p.score)
+    };
+    let first_wins = match score_of(first).cmp(&score_of(second)) {
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Less => false,
+        std::cmp::Ordering::Equal => {
+            let mut rng = GameRng::new(&game.entropy, &match_account.key.to_bytes());
+            rng.below(2) == 0
+        }
+    };
+    let (winner, loser) = if first_wins { (first, second) } else { (second, first) };
+
+    let round = tournament.bracket_round;
+    let eliminated = &mut tournament.entrants[loser as usize];
+    eliminated.alive = false;
+    eliminated.eliminated_round = round;
+
+    msg!(
+        "Bracket round {} match {} won by {}",
+        round,
+        index,
+        tournament.entrants[winner as usize].pubkey
+    );
+
+    // The next round starts once every pairing of this one has a winner
+    let mut fee = 0;
+    let survivors: Vec<u8> = tournament.remaining
+        .iter()
+        .copied()
+        .filter(|i| tournament.entrants[*i as usize].alive)
+        .collect();
+
+    if survivors.len() * 2 == tournament.remaining.len() {
+        if survivors.len() == 1 {
+            fee = calculate_fee(tournament.prize_pool, tournament.fee_basis_points);
+            tournament.prize_pool -= fee;
+            tournament.distribute_prizes(winner);
+            tournament.state = TournamentState::Finished;
+            msg!("Tournament won by {}", tournament.entrants[winner as usize].pubkey);
+        } else {
+            tournament.bracket_round += 1;
+            tournament.matches_opened = 0;
+        }
+        tournament.remaining = survivors;
+    }
+
+    tournament.save(tournament_account)?;
+
+    if fee > 0 {
+        transfer_from_vault(vault, treasury, fee)?;
+    }
+
+    close_account(match_account, match_host)?;
+
+    Ok(())
+}
+
+// Implementation for cancelling a tournament that has not started; every
+// entrant can then reclaim their buy-in. Once registration has timed out
+// anyone may cancel, so buy-ins are never stuck behind an absent organizer.
+pub fn process_cancel_tournament(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let caller = next_account_info(accounts_iter)?;
+    let tournament_account = next_account_info(accounts_iter)?;
+
+    // Ensure the caller signed the transaction
+    if !caller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut tournament = Tournament::load(program_id, tournament_account)?;
+
+    if tournament.state != TournamentState::Registration {
+        return Err(RpsError::TournamentNotOpen.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    if tournament.organizer != *caller.key && now < tournament.registration_deadline {
+        return Err(RpsError::NotTournamentOrganizer.into());
+    }
+
+    let buy_in = tournament.buy_in;
+    for entrant in tournament.entrants.iter_mut() {
+        entrant.payout = buy_in;
+    }
+    tournament.prize_pool = 0;
+    tournament.state = TournamentState::Cancelled;
+    tournament.save(tournament_account)?;
+
+    msg!("Tournament cancelled");
+
+    Ok(())
+}
+
+// Implementation for withdrawing a prize, or a buy-in from a cancelled tournament
+pub fn process_claim_tournament_payout(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let entrant = next_account_info(accounts_iter)?;
+    let tournament_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+
+    // Ensure the entrant signed the transaction
+    if !entrant.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut tournament = Tournament::load(program_id, tournament_account)?;
+    tournament.check_vault(program_id, tournament_account.key, vault)?;
+
+    if !matches!(tournament.state, TournamentState::Finished | TournamentState::Cancelled) {
+        return Err(RpsError::GameNotFinished.into());
+    }
+
+    let entrant_data = tournament.entrants
+        .iter_mut()
+        .find(|e| e.pubkey == *entrant.key)
+        .ok_or(RpsError::PlayerNotInGame)?;
+
+    if entrant_data.payout == 0 {
+        if entrant_data.claimed {
+            return Err(RpsError::AlreadyClaimed.into());
+        }
+        return Err(RpsError::NotAWinner.into());
+    }
+
+    let amount = entrant_data.payout;
+    entrant_data.payout = 0;
+    entrant_data.claimed = true;
+    tournament.save(tournament_account)?;
+
+    transfer_from_vault(vault, entrant, amount)?;
+
+    msg!("Tournament payout of {} claimed by {}", amount, entrant.key);
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, setup};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    access::{join_code_seed, GameAccess, MAX_INVITES},
+    error::RpsError,
+    instruction::{self, GameSettings},
+};
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{keypair_from_seed, Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+
+fn code_keypair(game: &Pubkey, code: &[u8]) -> Keypair {
+    keypair_from_seed(&join_code_seed(game, code)).unwrap()
+}
+
+#[test]
+fn join_codes_are_bound_to_their_game() {
+    let game = Pubkey::new_unique();
+    let code_key = code_keypair(&game, b"hunter2").pubkey();
+    let access = GameAccess::JoinCode(code_key);
+    let player = Pubkey::new_unique();
+
+    assert!(access.admit(&player, Some(&code_key)).is_ok());
+    assert!(access.admit(&player, Some(&code_keypair(&game, b"hunter3").pubkey())).is_err());
+    assert!(access.admit(&player, None).is_err());
+
+    // The same code opens a different key for another game
+    assert_ne!(code_keypair(&Pubkey::new_unique(), b"hunter2").pubkey(), code_key);
+}
+
+#[tokio::test]
+async fn allowlisted_games_only_seat_invited_wallets() {
+    let (mut env, players) = setup(3).await;
+    let game = Keypair::new();
+    let access = GameAccess::Allowlist(vec![players[1].pubkey()]);
+    let create = env.initialize_game(&players[0], &game, GameSettings { access, ..game_settings(ENTRY_FEE) });
+    env.process(&[create], &[&players[0], &game]).await.unwrap();
+    let game = game.pubkey();
+
+    let join = instruction::join_game(&env.program_id, &players[2].pubkey(), &game, None);
+    assert_custom_error(env.process(&[join], &[&players[2]]).await, RpsError::NotInvited);
+
+    env.join_sol_game(&players[1], &game).await;
+    assert_eq!(env.game(&game).await.players.len(), 2);
+}
+
+#[tokio::test]
+async fn code_games_seat_players_presenting_the_code() {
+    let (mut env, players) = setup(3).await;
+    let game = Keypair::new();
+    let code = code_keypair(&game.pubkey(), b"table-42");
+    let access = GameAccess::JoinCode(code.pubkey());
+    let create = env.initialize_game(&players[0], &game, GameSettings { access, ..game_settings(ENTRY_FEE) });
+    env.process(&[create], &[&players[0], &game]).await.unwrap();
+    let game = game.pubkey();
+
+    let join = instruction::join_game(&env.program_id, &players[1].pubkey(), &game, None);
+    assert_custom_error(env.process(&[join], &[&players[1]]).await, RpsError::InvalidJoinCode);
+
+    let wrong_code = code_keypair(&game, b"table-43");
+    let join = instruction::join_game(&env.program_id, &players[1].pubkey(), &game, None);
+    let wrong = instruction::with_join_code(join, &wrong_code.pubkey());
+    assert_custom_error(env.process(&[wrong], &[&players[1], &wrong_code]).await, RpsError::InvalidJoinCode);
+
+    // Naming the code key is not enough; it has to sign
+    let join = instruction::join_game(&env.program_id, &players[1].pubkey(), &game, None);
+    let mut unsigned = instruction::with_join_code(join, &code.pubkey());
+    unsigned.accounts[4].is_signer = false;
+    let result = env.process(&[unsigned], &[&players[1]]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
+    );
+
+    for player in &players[1..] {
+        let join = instruction::join_game(&env.program_id, &player.pubkey(), &game, None);
+        let join = instruction::with_join_code(join, &code.pubkey());
+        env.process(&[join], &[player, &code]).await.unwrap();
+    }
+    assert_eq!(env.game(&game).await.players.len(), 3);
+}
+
+#[tokio::test]
+async fn only_the_host_fills_private_games_with_bots() {
+    let (mut env, players) = setup(2).await;
+    let game = Keypair::new();
+    let access = GameAccess::Allowlist(vec![players[1].pubkey()]);
+    let create = env.initialize_game(&players[0], &game, GameSettings { access, ..game_settings(ENTRY_FEE) });
+    env.process(&[create], &[&players[0], &game]).await.unwrap();
+    let game = game.pubkey();
+    env.join_sol_game(&players[1], &game).await;
+
+    let add_bot = instruction::add_bot_players(&env.program_id, &players[1].pubkey(), &game, None, 1);
+    assert_custom_error(env.process(&[add_bot], &[&players[1]]).await, RpsError::NotInvited);
+
+    let add_bot = instruction::add_bot_players(&env.program_id, &players[0].pubkey(), &game, None, 1);
+    env.process(&[add_bot], &[&players[0]]).await.unwrap();
+    assert_eq!(env.game(&game).await.players.len(), 3);
+}
+
+#[tokio::test]
+async fn invalid_or_listed_private_games_are_rejected_at_creation() {
+    let (mut env, players) = setup(1).await;
+
+    let too_many = GameAccess::Allowlist(vec![Pubkey::new_unique(); MAX_INVITES + 1]);
+    for access in [GameAccess::Allowlist(Vec::new()), too_many, GameAccess::JoinCode(Pubkey::default())] {
+        let game = Keypair::new();
+        let create = env.initialize_game(&players[0], &game, GameSettings { access, ..game_settings(ENTRY_FEE) });
+        assert_custom_error(env.process(&[create], &[&players[0], &game]).await, RpsError::InvalidGameAccess);
+    }
+
+    let create = instruction::initialize_lobby(&env.program_id, &env.payer());
+    env.process(&[create], &[]).await.unwrap();
+    let game = Keypair::new();
+    let access = GameAccess::Allowlist(vec![players[0].pubkey()]);
+    let create = env.initialize_game(&players[0], &game, GameSettings { access, ..game_settings(ENTRY_FEE) });
+    let listed = instruction::with_lobby(create);
+    assert_custom_error(env.process(&[listed], &[&players[0], &game]).await, RpsError::PrivateGameListed);
+}
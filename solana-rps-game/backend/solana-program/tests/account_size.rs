@@ -0,0 +1,134 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+use solana_rps_game::{
+    access::{GameAccess, MAX_INVITES},
+    payouts::PayoutTable,
+    profile::PlayerProfile,
+    rules::{RuleSet, MAX_CHOICES},
+    scoring::ScoringModel,
+    AccountType, Choice, CurrencyMode, Game, GameMode, GameState, Player, GAME_LAYOUT_VERSION,
+    GAME_RESERVED_SPACE,
+};
+
+// A game with every player seat taken and every field at its widest value
+fn full_game(max_players: u8) -> Game {
+    let players = (0..max_players)
+        .map(|_| Player {
+            pubkey: Pubkey::new_unique(),
+            choice: Choice::SCISSORS,
+            committed_choice: [u8::MAX; 32],
+            revealed: true,
+            score: u8::MAX,
+            claimed: true,
+            payout: u64::MAX,
+            is_bot: true,
+            funder: Pubkey::new_unique(),
+            eliminated: true,
+            rounds_won: u8::MAX,
+            wagered: u64::MAX,
+            winnings: u64::MAX,
+        })
+        .collect();
+
+    Game {
+        account_type: AccountType::Game,
+        layout_version: GAME_LAYOUT_VERSION,
+        host: Pubkey::new_unique(),
+        players,
+        min_players: 3,
+        max_players,
+        state: GameState::Cancelled,
+        current_round: u8::MAX,
+        total_rounds: u8::MAX,
+        entry_fee: u64::MAX,
+        game_pot: u64::MAX,
+        required_timeout: u64::MAX,
+        last_action_timestamp: u64::MAX,
+        player_count: max_players,
+        losers_can_rejoin: true,
+        game_mode: GameMode::BattleRoyale,
+        auto_round_delay: u64::MAX,
+        max_auto_rounds: u64::MAX,
+        current_auto_round: u64::MAX,
+        currency_mode: CurrencyMode::RPSToken,
+        vault_bump: u8::MAX,
+        token_mint: Pubkey::new_unique(),
+        fee_basis_points: u16::MAX,
+        fees_owed: u64::MAX,
+        commit_timeout: u64::MAX,
+        reveal_timeout: u64::MAX,
+        commit_deadline: u64::MAX,
+        reveal_deadline: u64::MAX,
+        entropy: [u8::MAX; 32],
+        tournament: Pubkey::new_unique(),
+        survivors: u8::MAX,
+        opponent: Pubkey::new_unique(),
+        rule_set: RuleSet::Custom(vec![u16::MAX; MAX_CHOICES]),
+        scoring_model: ScoringModel::MajorityLoses,
+        payout_table: PayoutTable::TopTwo { first_bps: u16::MAX, second_bps: u16::MAX },
+        min_rating: u16::MAX,
+        max_rating: u16::MAX,
+        access: GameAccess::Allowlist(vec![Pubkey::new_unique(); MAX_INVITES]),
+        count_seed_hash: [u8::MAX; 32],
+        game_expiry_seconds: u64::MAX,
+        count_seed_deadline: u64::MAX,
+    }
+}
+
+#[test]
+fn full_game_matches_computed_size() {
+    for max_players in [2, 3, 4] {
+        let game = full_game(max_players);
+        let data = game.try_to_vec().unwrap();
+        assert_eq!(data.len(), Game::serialized_len(max_players));
+        assert_eq!(Game::space(max_players), data.len() + GAME_RESERVED_SPACE);
+    }
+}
+
+#[test]
+fn full_game_round_trips_through_allocated_account() {
+    for max_players in [2, 3, 4] {
+        let game = full_game(max_players);
+        let mut account = vec![0u8; Game::space(max_players)];
+        game.serialize(&mut &mut account[..]).unwrap();
+
+        // The zeroed reserve is ignored when loading
+        let loaded = Game::deserialize(&mut &account[..]).unwrap();
+        assert_eq!(loaded.try_to_vec().unwrap(), game.try_to_vec().unwrap());
+    }
+}
+
+#[test]
+fn saving_a_shorter_game_zeroes_the_rest_of_the_account() {
+    let key = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let mut lamports = 0;
+    let mut data = vec![0u8; Game::space(4)];
+    let account = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+
+    full_game(4).save(&account).unwrap();
+    let shorter = full_game(2);
+    shorter.save(&account).unwrap();
+
+    // Fields appended later are read from the zeroed bytes after the game
+    let data = account.data.borrow();
+    let len = Game::serialized_len(2);
+    assert_eq!(data[..len], shorter.try_to_vec().unwrap()[..]);
+    assert!(data[len..].iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn full_profile_matches_computed_size() {
+    let profile = PlayerProfile {
+        wallet: Pubkey::new_unique(),
+        games_played: u64::MAX,
+        rounds_won: u64::MAX,
+        choice_counts: [u64::MAX; MAX_CHOICES],
+        total_wagered: u64::MAX,
+        total_won: u64::MAX,
+        bump: u8::MAX,
+        rating: u16::MAX,
+    };
+    assert_eq!(profile.try_to_vec().unwrap().len(), PlayerProfile::LEN);
+    assert_eq!(PlayerProfile::space(), PlayerProfile::LEN + GAME_RESERVED_SPACE);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    error::RpsError,
+    instruction::{self, GameSettings},
+    Choice, GameState,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+
+async fn create_battle_royale(env: &mut TestEnv, players: &[Keypair], survivors: u8) -> Pubkey {
+    let game = Keypair::new();
+    let initialize = env.initialize_game(&players[0], &game, GameSettings { game_mode: 2, survivors, ..game_settings(ENTRY_FEE) });
+    env.process(&[initialize], &[&players[0], &game]).await.unwrap();
+    for player in &players[1..] {
+        env.join_sol_game(player, &game.pubkey()).await;
+    }
+    game.pubkey()
+}
+
+#[tokio::test]
+async fn lowest_scorers_are_knocked_out_until_one_remains() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = create_battle_royale(&mut env, &players, 1).await;
+    let all: Vec<&Keypair> = players.iter().collect();
+
+    // A round where everyone ties knocks nobody out and is played again,
+    // even past `total_rounds`
+    env.play_round(&game, &all, &[Choice::ROCK, Choice::ROCK, Choice::ROCK]).await;
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::CommitPhase);
+    assert_eq!(state.current_round, 2);
+    assert_eq!(state.active_player_count(), 3);
+
+    // The single lowest scorer is out
+    env.play_round(&game, &all, &[Choice::ROCK, Choice::ROCK, Choice::SCISSORS]).await;
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::CommitPhase);
+    assert!(state.players[2].eliminated);
+    assert_eq!(state.active_player_count(), 2);
+
+    // Eliminated players can no longer commit and are not waited for
+    let commitment = compute_commitment(&game, 3, &players[2].pubkey(), choice_byte(&Choice::ROCK).unwrap(), &[7u8; 32]);
+    let commit = instruction::commit_choice(&env.program_id, &players[2].pubkey(), &game, commitment);
+    assert_custom_error(env.process(&[commit], &[&players[2]]).await, RpsError::PlayerEliminated);
+
+    env.play_round(&game, &all[..2], &[Choice::ROCK, Choice::PAPER]).await;
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert!(state.players[0].eliminated);
+
+    // The last survivor takes the whole pot
+    assert_eq!(state.players[1].payout, 3 * ENTRY_FEE);
+    assert_eq!(state.players[0].payout, 0);
+    assert_eq!(state.players[2].payout, 0);
+}
+
+#[tokio::test]
+async fn survivors_split_the_pot() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = create_battle_royale(&mut env, &players, 2).await;
+    let all: Vec<&Keypair> = players.iter().collect();
+
+    // Knocking out both scissors players would leave fewer than two survivors
+    env.play_round(&game, &all, &[Choice::ROCK, Choice::SCISSORS, Choice::SCISSORS]).await;
+    assert_eq!(env.game(&game).await.active_player_count(), 3);
+
+    env.play_round(&game, &all, &[Choice::ROCK, Choice::ROCK, Choice::SCISSORS]).await;
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert_eq!(state.players[0].payout, 3 * ENTRY_FEE / 2);
+    assert_eq!(state.players[1].payout, 3 * ENTRY_FEE / 2);
+    assert_eq!(state.players[2].payout, 0);
+}
+
+#[tokio::test]
+async fn survivor_count_must_knock_someone_out() {
+    let (mut env, players) = start(1).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    for survivors in [0, 3] {
+        let game = Keypair::new();
+        let initialize = env.initialize_game(&players[0], &game, GameSettings { game_mode: 2, survivors, ..game_settings(ENTRY_FEE) });
+        assert_custom_error(
+            env.process(&[initialize], &[&players[0], &game]).await,
+            RpsError::InvalidSurvivorCount,
+        );
+    }
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    error::RpsError,
+    find_bot_address,
+    instruction::{self, GameSettings},
+    randomness::{mix_entropy, GameRng},
+    Choice, GameState,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    sysvar,
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+
+async fn create_game(env: &mut TestEnv, host: &Keypair, total_rounds: u8) -> Pubkey {
+    let game = Keypair::new();
+    let settings = GameSettings {
+        total_rounds,
+        game_mode: 1,
+        max_auto_rounds: 1,
+        ..game_settings(ENTRY_FEE)
+    };
+    let initialize = env.initialize_game(host, &game, settings);
+    env.process(&[initialize], &[host, &game]).await.unwrap();
+    game.pubkey()
+}
+
+#[tokio::test]
+async fn bots_play_every_round_without_signing() {
+    let (mut env, players) = start(2).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = create_game(&mut env, &players[0], 2).await;
+    env.join_sol_game(&players[1], &game).await;
+
+    let add_bot = instruction::add_bot_players(&env.program_id, &players[0].pubkey(), &game, None, 1);
+    env.process(&[add_bot], &[&players[0]]).await.unwrap();
+
+    // The bot takes the last seat under its PDA identity
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::CommitPhase);
+    assert_eq!(state.players[2].pubkey, find_bot_address(&game, 2, &env.program_id).0);
+    assert!(state.players[2].is_bot);
+    assert!(!state.players[0].is_bot && !state.players[1].is_bot);
+
+    // Humans alone move the game through both rounds
+    let humans: Vec<&Keypair> = players.iter().collect();
+    env.play_round(&game, &humans, &[Choice::ROCK, Choice::PAPER]).await;
+
+    let state = env.game(&game).await;
+    assert_eq!(state.current_round, 2);
+    assert_eq!(state.state, GameState::CommitPhase);
+
+    // Paper beats Rock, and the bot's drawn move adds one or two more wins
+    let scores: u32 = state.players.iter().map(|p| p.score as u32).sum();
+    assert!((2..=3).contains(&scores));
+
+    env.play_round(&game, &humans, &[Choice::SCISSORS, Choice::SCISSORS]).await;
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert!(!matches!(state.players[2].choice, Choice::NONE));
+    assert!(state.players[2].revealed);
+}
+
+#[tokio::test]
+async fn bots_play_when_the_reveal_phase_times_out() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = create_game(&mut env, &players[0], 1).await;
+    env.join_sol_game(&players[1], &game).await;
+
+    let add_bot = instruction::add_bot_players(&env.program_id, &players[0].pubkey(), &game, None, 1);
+    env.process(&[add_bot], &[&players[0]]).await.unwrap();
+
+    // Both humans commit but only the host reveals
+    let salt = [5u8; 32];
+    for (player, choice) in players[..2].iter().zip([Choice::ROCK, Choice::PAPER]) {
+        let committed_choice =
+            compute_commitment(&game, 1, &player.pubkey(), choice_byte(&choice).unwrap(), &salt);
+        let commit = instruction::commit_choice(&env.program_id, &player.pubkey(), &game, committed_choice);
+
+        // The commit closing the phase seeds the bots from the real SlotHashes sysvar
+        if player.pubkey() == players[1].pubkey() {
+            let mut spoofed = commit.clone();
+            spoofed.accounts[2].pubkey = sysvar::clock::id();
+            assert_custom_error(env.process(&[spoofed], &[player]).await, RpsError::InvalidSlotHashes);
+        }
+        env.process(&[commit], &[player]).await.unwrap();
+    }
+    let reveal = instruction::reveal_choice(&env.program_id, &players[0].pubkey(), &game, Choice::ROCK, salt);
+    env.process(&[reveal], &[&players[0]]).await.unwrap();
+
+    env.advance_clock(60).await;
+    let timeout = instruction::resolve_timeout(&env.program_id, &players[2].pubkey(), &game);
+    let timeout = env.with_human_profiles(timeout, &game).await;
+    env.process(&[timeout], &[&players[2]]).await.unwrap();
+
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert!(matches!(state.players[1].choice, Choice::NONE));
+    assert!(!matches!(state.players[2].choice, Choice::NONE));
+}
+
+#[tokio::test]
+async fn bot_moves_are_fixed_before_anyone_reveals() {
+    let (mut env, players) = start(2).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = create_game(&mut env, &players[0], 1).await;
+    env.join_sol_game(&players[1], &game).await;
+
+    let add_bot = instruction::add_bot_players(&env.program_id, &players[0].pubkey(), &game, None, 1);
+    env.process(&[add_bot], &[&players[0]]).await.unwrap();
+
+    let moves = [(Choice::ROCK, [5u8; 32]), (Choice::PAPER, [6u8; 32])];
+    for (player, (choice, salt)) in players.iter().zip(moves) {
+        let committed_choice =
+            compute_commitment(&game, 1, &player.pubkey(), choice_byte(&choice).unwrap(), &salt);
+        let commit = instruction::commit_choice(&env.program_id, &player.pubkey(), &game, committed_choice);
+        env.process(&[commit], &[player]).await.unwrap();
+    }
+
+    // Once the commit phase closes the bot's move follows from the committed salts alone
+    let mut pool = env.game(&game).await.entropy;
+    for (player, (_, salt)) in players.iter().zip(moves) {
+        mix_entropy(&mut pool, &[player.pubkey().as_ref(), &salt]);
+    }
+    let expected = Choice::new(GameRng::new(&pool, &game.to_bytes()).below(3) as u8 + 1);
+
+    // Waiting for other slots before the last reveal does not change it
+    let first = instruction::reveal_choice(&env.program_id, &players[0].pubkey(), &game, moves[0].0, moves[0].1);
+    env.process(&[first], &[&players[0]]).await.unwrap();
+    for _ in 0..5 {
+        env.advance_clock(0).await;
+    }
+    let last = instruction::reveal_choice(&env.program_id, &players[1].pubkey(), &game, moves[1].0, moves[1].1);
+    let last = env.with_human_profiles(last, &game).await;
+    env.process(&[last], &[&players[1]]).await.unwrap();
+
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert_eq!(state.players[2].choice, expected);
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, start};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    config::MIN_GAME_EXPIRY_SECONDS,
+    error::RpsError,
+    instruction::{self, GameSettings},
+    Choice,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+
+#[tokio::test]
+async fn rejoin_fees_keep_a_finished_game_open() {
+    rejoin_fee_keeps_a_finished_game_open(ENTRY_FEE).await;
+}
+
+// A single-unit rejoin fee is less than the dust a three-way split can leave
+#[tokio::test]
+async fn tiny_rejoin_fees_keep_a_finished_game_open() {
+    rejoin_fee_keeps_a_finished_game_open(1).await;
+}
+
+async fn rejoin_fee_keeps_a_finished_game_open(entry_fee: u64) {
+    let (mut env, players) = start(3).await;
+    let treasury = Pubkey::new_unique();
+    env.initialize_config(0, treasury, 86_400).await;
+
+    let game = Keypair::new();
+    let settings = GameSettings { losers_can_rejoin: true, ..game_settings(entry_fee) };
+    let create = env.initialize_game(&players[0], &game, settings);
+    env.process(&[create], &[&players[0], &game]).await.unwrap();
+    let game = game.pubkey();
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.play_round(&game, &signers, &[Choice::ROCK, Choice::SCISSORS, Choice::SCISSORS]).await;
+    let claim = instruction::claim_winnings(&env.program_id, &players[0].pubkey(), &game, None);
+    env.process(&[claim], &[&players[0]]).await.unwrap();
+    assert!(env.game(&game).await.payouts_complete());
+
+    // A loser buys back in; the game is still Finished but now holds their fee
+    let rejoin = instruction::rejoin_game(&env.program_id, &players[1].pubkey(), &game, None);
+    env.process(&[rejoin], &[&players[1]]).await.unwrap();
+    let state = env.game(&game).await;
+    assert_eq!(state.game_pot, entry_fee);
+    assert!(!state.payouts_complete());
+
+    let close = instruction::close_game(
+        &env.program_id,
+        &players[2].pubkey(),
+        &game,
+        &players[0].pubkey(),
+        &treasury,
+        None,
+    );
+    assert_custom_error(env.process(&[close], &[&players[2]]).await, RpsError::PayoutsPending);
+    assert_eq!(env.lamports(&treasury).await, 0);
+}
+
+#[tokio::test]
+async fn game_expiry_must_be_at_least_a_day() {
+    let (mut env, _) = start(0).await;
+    let treasury = Pubkey::new_unique();
+
+    let short = instruction::initialize_config(&env.program_id, &env.payer(), 0, treasury, MIN_GAME_EXPIRY_SECONDS - 1);
+    assert_custom_error(env.process(&[short], &[]).await, RpsError::GameExpiryTooShort);
+    env.initialize_config(0, treasury, MIN_GAME_EXPIRY_SECONDS).await;
+
+    let payer = env.payer();
+    let update = instruction::update_config(&env.program_id, &payer, payer, 0, treasury, 0);
+    assert_custom_error(env.process(&[update], &[]).await, RpsError::GameExpiryTooShort);
+}
+
+#[tokio::test]
+async fn games_keep_the_expiry_they_were_created_with() {
+    let (mut env, players) = start(3).await;
+    let treasury = Pubkey::new_unique();
+    env.initialize_config(0, treasury, 2 * MIN_GAME_EXPIRY_SECONDS).await;
+
+    let (game, _) = env.create_sol_game(&players[0], ENTRY_FEE, 300).await;
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.play_round(&game, &signers, &[Choice::ROCK, Choice::SCISSORS, Choice::SCISSORS]).await;
+
+    // Shortening the expiry afterwards does not reach the unclaimed winnings
+    let payer = env.payer();
+    let update = instruction::update_config(&env.program_id, &payer, payer, 0, treasury, MIN_GAME_EXPIRY_SECONDS);
+    env.process(&[update], &[]).await.unwrap();
+    env.advance_clock(MIN_GAME_EXPIRY_SECONDS as i64).await;
+
+    let program_id = env.program_id;
+    let close = || instruction::close_game(&program_id, &players[1].pubkey(), &game, &players[0].pubkey(), &treasury, None);
+    assert_custom_error(env.process(&[close()], &[&players[1]]).await, RpsError::PayoutsPending);
+
+    env.advance_clock(MIN_GAME_EXPIRY_SECONDS as i64).await;
+    env.process(&[close()], &[&players[1]]).await.unwrap();
+    assert_eq!(env.lamports(&treasury).await, 3 * ENTRY_FEE);
+}
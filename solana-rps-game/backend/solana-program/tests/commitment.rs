@@ -0,0 +1,94 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    error::RpsError,
+    instruction::{self, GameSettings},
+    Choice, GameState,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+const SALT: [u8; 32] = [9u8; 32];
+
+// Two round game so a commitment can also be replayed in a later round
+async fn create_two_round_game(env: &mut TestEnv, players: &[Keypair]) -> Pubkey {
+    let game = Keypair::new();
+    let settings = GameSettings {
+        total_rounds: 2,
+        ..game_settings(ENTRY_FEE)
+    };
+    let initialize = env.initialize_game(&players[0], &game, settings);
+    env.process(&[initialize], &[&players[0], &game]).await.unwrap();
+    env.join_sol_game(&players[1], &game.pubkey()).await;
+    env.join_sol_game(&players[2], &game.pubkey()).await;
+    game.pubkey()
+}
+
+#[tokio::test]
+async fn copied_commitments_cannot_be_revealed() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    let game = create_two_round_game(&mut env, &players).await;
+
+    let rock = choice_byte(&Choice::ROCK).unwrap();
+    let original = compute_commitment(&game, 1, &players[0].pubkey(), rock, &SALT);
+
+    // The commitment differs for every other player, round and game
+    assert_ne!(original, compute_commitment(&game, 1, &players[1].pubkey(), rock, &SALT));
+    assert_ne!(original, compute_commitment(&game, 2, &players[0].pubkey(), rock, &SALT));
+    assert_ne!(original, compute_commitment(&Pubkey::new_unique(), 1, &players[0].pubkey(), rock, &SALT));
+
+    // Player 1 copies player 0's commitment in the same round
+    let paper = choice_byte(&Choice::PAPER).unwrap();
+    let commitments = [
+        original,
+        original,
+        compute_commitment(&game, 1, &players[2].pubkey(), paper, &SALT),
+    ];
+    for (player, committed_choice) in players.iter().zip(commitments) {
+        let commit = instruction::commit_choice(&env.program_id, &player.pubkey(), &game, committed_choice);
+        env.process(&[commit], &[player]).await.unwrap();
+    }
+    assert_eq!(env.game(&game).await.state, GameState::RevealPhase);
+
+    let copied = instruction::reveal_choice(&env.program_id, &players[1].pubkey(), &game, Choice::ROCK, SALT);
+    let result = env.process(&[copied], &[&players[1]]).await;
+    assert_custom_error(result, RpsError::CommitmentMismatch);
+
+    // The original owner can still reveal
+    let reveal = instruction::reveal_choice(&env.program_id, &players[0].pubkey(), &game, Choice::ROCK, SALT);
+    env.process(&[reveal], &[&players[0]]).await.unwrap();
+}
+
+#[tokio::test]
+async fn commitments_from_an_earlier_round_are_rejected() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    let game = create_two_round_game(&mut env, &players).await;
+
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.play_round(&game, &signers, &[Choice::ROCK, Choice::PAPER, Choice::SCISSORS]).await;
+    assert_eq!(env.game(&game).await.current_round, 2);
+
+    // Everyone replays player 0's round one commitment (play_round salts with 7s) in round two
+    let rock = choice_byte(&Choice::ROCK).unwrap();
+    let stale = compute_commitment(&game, 1, &players[0].pubkey(), rock, &[7u8; 32]);
+    for player in &players {
+        let commit = instruction::commit_choice(&env.program_id, &player.pubkey(), &game, stale);
+        env.process(&[commit], &[player]).await.unwrap();
+    }
+
+    for player in &players {
+        let reveal = instruction::reveal_choice(&env.program_id, &player.pubkey(), &game, Choice::ROCK, [7u8; 32]);
+        let result = env.process(&[reveal], &[player]).await;
+        assert_custom_error(result, RpsError::CommitmentMismatch);
+    }
+}
@@ -0,0 +1,346 @@
+#![allow(dead_code)]
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+};
+use solana_program_test::{processor, tokio::sync::Mutex, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    config::{find_config_address, ProgramConfig},
+    find_vault_address,
+    instruction::{self, GameSettings},
+    process_instruction, Choice, Game,
+};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const PLAYER_LAMPORTS: u64 = 10_000_000_000;
+
+// The settings most tests start from: an open three player, one round game
+// whose lobby, commit and reveal phases each time out after a minute
+pub fn game_settings(entry_fee: u64) -> GameSettings {
+    GameSettings {
+        entry_fee,
+        timeout_seconds: 60,
+        commit_timeout_seconds: 60,
+        reveal_timeout_seconds: 60,
+        ..GameSettings::default()
+    }
+}
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub program_id: Pubkey,
+}
+
+// Build a program test with the RPS processor and `player_count` funded wallets
+pub async fn start(player_count: usize) -> (TestEnv, Vec<Keypair>) {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "solana_rps_game",
+        program_id,
+        processor!(process_instruction),
+    );
+
+    let players: Vec<Keypair> = (0..player_count).map(|_| Keypair::new()).collect();
+    for player in &players {
+        program_test.add_account(
+            player.pubkey(),
+            Account {
+                lamports: PLAYER_LAMPORTS,
+                ..Account::default()
+            },
+        );
+    }
+
+    let context = if STUBS_WRAPPED.load(Ordering::Acquire) {
+        program_test.start_with_context().await
+    } else {
+        // The runtime installs its stubs when the first test starts; starts are
+        // serialized until they are wrapped, so no transaction runs mid-swap
+        let _start = FIRST_START.lock().await;
+        let context = program_test.start_with_context().await;
+        if !STUBS_WRAPPED.load(Ordering::Acquire) {
+            let runtime = set_syscall_stubs(Box::new(NoStubs));
+            set_syscall_stubs(Box::new(LogDataStubs(runtime)));
+            STUBS_WRAPPED.store(true, Ordering::Release);
+        }
+        context
+    };
+
+    (TestEnv { context, program_id }, players)
+}
+
+// `start` followed by a fee-free config with a one day game expiry
+pub async fn setup(player_count: usize) -> (TestEnv, Vec<Keypair>) {
+    let (mut env, players) = start(player_count).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    (env, players)
+}
+
+// Prefix of the log lines `LogDataStubs` turns `sol_log_data` records into
+const LOG_DATA_PREFIX: &str = "Program log: data: ";
+
+// The native test runtime prints `sol_log_data` records to stdout instead of
+// the transaction logs. These stubs wrap the runtime's own and route records
+// through `sol_log`; `process_logged` restores the validator's format.
+struct LogDataStubs(Box<dyn SyscallStubs>);
+
+impl SyscallStubs for LogDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+    fn sol_log_compute_units(&self) {
+        self.0.sol_log_compute_units()
+    }
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.0.sol_remaining_compute_units()
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memcpy(dst, src, n)
+    }
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memmove(dst, src, n)
+    }
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        self.0.sol_memcmp(s1, s2, n, result)
+    }
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        self.0.sol_memset(s, c, n)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let encoded: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        self.0.sol_log(&format!("data: {}", encoded.join(" ")))
+    }
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.0.sol_get_processed_sibling_instruction(index)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+// Placeholder held while the runtime's stubs are moved into `LogDataStubs`
+struct NoStubs;
+
+impl SyscallStubs for NoStubs {}
+
+static STUBS_WRAPPED: AtomicBool = AtomicBool::new(false);
+static FIRST_START: Mutex<()> = Mutex::const_new(());
+
+impl TestEnv {
+    async fn transaction(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+        // A fresh blockhash keeps identical transactions from being deduplicated
+        let recent_blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+
+        Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            recent_blockhash,
+        )
+    }
+
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let transaction = self.transaction(instructions, signers).await;
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    // Process a transaction that must succeed and return its log messages
+    pub async fn process_logged(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Vec<String> {
+        let transaction = self.transaction(instructions, signers).await;
+        let processed = self.context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+        processed.result.unwrap();
+        processed.metadata
+            .unwrap()
+            .log_messages
+            .into_iter()
+            .map(|line| match line.strip_prefix(LOG_DATA_PREFIX) {
+                Some(data) => format!("Program data: {}", data),
+                None => line,
+            })
+            .collect()
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    // Move the cluster clock forward by `seconds`
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        // Warp to a new slot so later transactions get a fresh blockhash
+        self.context.warp_to_slot(clock.slot + 2).unwrap();
+        clock.slot += 2;
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub fn config_address(&self) -> Pubkey {
+        find_config_address(&self.program_id).0
+    }
+
+    // Treasury the config sends protocol fees to
+    pub async fn treasury(&mut self) -> Pubkey {
+        let account = self.context.banks_client.get_account(self.config_address()).await.unwrap().unwrap();
+        ProgramConfig::deserialize(&mut &account.data[..]).unwrap().treasury
+    }
+
+    // Create the global config with the payer as admin
+    pub async fn initialize_config(
+        &mut self,
+        fee_basis_points: u16,
+        treasury: Pubkey,
+        game_expiry_seconds: u64,
+    ) {
+        let instruction = instruction::initialize_config(
+            &self.program_id,
+            &self.payer(),
+            fee_basis_points,
+            treasury,
+            game_expiry_seconds,
+        );
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    // InitializeGame for a SOL game hosted by `host`
+    pub fn initialize_game(&self, host: &Keypair, game: &Keypair, settings: GameSettings) -> Instruction {
+        instruction::initialize_game(&self.program_id, &host.pubkey(), &game.pubkey(), None, settings)
+    }
+
+    // Host a three player SOL game where the lobby, commit and reveal phases
+    // all time out after `timeout_seconds`; returns the game and vault addresses
+    pub async fn create_sol_game(
+        &mut self,
+        host: &Keypair,
+        entry_fee: u64,
+        timeout_seconds: u64,
+    ) -> (Pubkey, Pubkey) {
+        let game = Keypair::new();
+        let (vault, _) = find_vault_address(&game.pubkey(), &self.program_id);
+        let settings = GameSettings {
+            timeout_seconds,
+            commit_timeout_seconds: timeout_seconds,
+            reveal_timeout_seconds: timeout_seconds,
+            ..game_settings(entry_fee)
+        };
+        let instruction = self.initialize_game(host, &game, settings);
+        self.process(&[instruction], &[host, &game]).await.unwrap();
+        (game.pubkey(), vault)
+    }
+
+    pub async fn join_sol_game(&mut self, player: &Keypair, game: &Pubkey) {
+        let instruction = instruction::join_game(&self.program_id, &player.pubkey(), game, None);
+        self.process(&[instruction], &[player]).await.unwrap();
+    }
+
+    // Commit and then reveal `choice` for every player, in order
+    pub async fn play_round(&mut self, game: &Pubkey, players: &[&Keypair], choices: &[Choice]) {
+        let salt = [7u8; 32];
+        let state = self.game(game).await;
+        let round = state.current_round;
+        let humans = human_players(&state);
+        for (player, choice) in players.iter().zip(choices.iter()) {
+            let choice_byte = choice_byte(choice).unwrap();
+            let instruction = instruction::commit_choice(
+                &self.program_id,
+                &player.pubkey(),
+                game,
+                compute_commitment(game, round, &player.pubkey(), choice_byte, &salt),
+            );
+            self.process(&[instruction], &[player]).await.unwrap();
+        }
+
+        for (player, choice) in players.iter().zip(choices.iter()) {
+            let instruction = instruction::reveal_choice(
+                &self.program_id,
+                &player.pubkey(),
+                game,
+                *choice,
+                salt,
+            );
+            let instruction = instruction::with_profiles(instruction, &humans);
+            self.process(&[instruction], &[player]).await.unwrap();
+        }
+    }
+
+    // Pass the profiles of every human player, as an instruction that may settle the game must
+    pub async fn with_human_profiles(&mut self, instruction: Instruction, game: &Pubkey) -> Instruction {
+        let humans = human_players(&self.game(game).await);
+        instruction::with_profiles(instruction, &humans)
+    }
+
+    pub async fn game(&mut self, game: &Pubkey) -> Game {
+        let account = self.context.banks_client.get_account(*game).await.unwrap().unwrap();
+        Game::deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context.banks_client.get_balance(*address).await.unwrap()
+    }
+}
+
+// Wallets of a game's human players, whose profiles settling the game needs
+pub fn human_players(game: &Game) -> Vec<Pubkey> {
+    game.players.iter().filter(|p| !p.is_bot).map(|p| p.pubkey).collect()
+}
+
+// Assert that a transaction failed with the given custom program error
+pub fn assert_custom_error(result: Result<(), BanksClientError>, error: solana_rps_game::error::RpsError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, error as u32)
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
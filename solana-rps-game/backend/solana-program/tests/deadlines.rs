@@ -0,0 +1,115 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, start, TestEnv};
+use solana_program_test::{tokio, BanksClientError};
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    error::RpsError,
+    instruction::{self, GameSettings},
+    Choice, GameState,
+};
+use solana_sdk::{
+    clock::Clock,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+const COMMIT_TIMEOUT: u64 = 100;
+const REVEAL_TIMEOUT: u64 = 200;
+const SALT: [u8; 32] = [3u8; 32];
+
+async fn create_full_game(env: &mut TestEnv, players: &[Keypair]) -> Pubkey {
+    let game = Keypair::new();
+    let settings = GameSettings {
+        commit_timeout_seconds: COMMIT_TIMEOUT,
+        reveal_timeout_seconds: REVEAL_TIMEOUT,
+        ..game_settings(ENTRY_FEE)
+    };
+    let initialize = env.initialize_game(&players[0], &game, settings);
+    env.process(&[initialize], &[&players[0], &game]).await.unwrap();
+    env.join_sol_game(&players[1], &game.pubkey()).await;
+    env.join_sol_game(&players[2], &game.pubkey()).await;
+    game.pubkey()
+}
+
+async fn now(env: &mut TestEnv) -> u64 {
+    let clock: Clock = env.context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp as u64
+}
+
+async fn commit(env: &mut TestEnv, game: &Pubkey, player: &Keypair, choice: &Choice) -> Result<(), BanksClientError> {
+    let round = env.game(game).await.current_round;
+    let committed_choice = compute_commitment(game, round, &player.pubkey(), choice_byte(choice).unwrap(), &SALT);
+    let instruction = instruction::commit_choice(&env.program_id, &player.pubkey(), game, committed_choice);
+    env.process(&[instruction], &[player]).await
+}
+
+#[tokio::test]
+async fn trickled_commits_do_not_extend_the_commit_phase() {
+    let (mut env, players) = start(4).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = create_full_game(&mut env, &players[..3]).await;
+
+    let started = now(&mut env).await;
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::CommitPhase);
+    assert_eq!(state.commit_deadline, started + COMMIT_TIMEOUT);
+
+    commit(&mut env, &game, &players[0], &Choice::ROCK).await.unwrap();
+    env.advance_clock(60).await;
+    commit(&mut env, &game, &players[1], &Choice::PAPER).await.unwrap();
+    assert_eq!(env.game(&game).await.commit_deadline, started + COMMIT_TIMEOUT);
+
+    // Only 40 seconds since the last commit, but the phase deadline has passed
+    env.advance_clock(40).await;
+    let late = commit(&mut env, &game, &players[2], &Choice::SCISSORS).await;
+    assert_custom_error(late, RpsError::CommitDeadlinePassed);
+
+    let timeout = instruction::resolve_timeout(&env.program_id, &players[3].pubkey(), &game);
+    let timeout = env.with_human_profiles(timeout, &game).await;
+    env.process(&[timeout], &[&players[3]]).await.unwrap();
+    assert_eq!(env.game(&game).await.state, GameState::Finished);
+}
+
+#[tokio::test]
+async fn reveal_deadline_is_fixed_when_the_reveal_phase_begins() {
+    let (mut env, players) = start(4).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = create_full_game(&mut env, &players[..3]).await;
+
+    let choices = [Choice::ROCK, Choice::SCISSORS, Choice::SCISSORS];
+    for (player, choice) in players.iter().zip(choices.iter()) {
+        commit(&mut env, &game, player, choice).await.unwrap();
+    }
+
+    let revealed_at = now(&mut env).await;
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::RevealPhase);
+    assert_eq!(state.reveal_deadline, revealed_at + REVEAL_TIMEOUT);
+
+    for (player, choice) in players.iter().zip(choices.iter()).take(2) {
+        let reveal = instruction::reveal_choice(&env.program_id, &player.pubkey(), &game, *choice, SALT);
+        env.process(&[reveal], &[player]).await.unwrap();
+    }
+
+    // The reveal phase runs for its own duration, not the commit phase's
+    env.advance_clock(REVEAL_TIMEOUT as i64 - 1).await;
+    let early = instruction::resolve_timeout(&env.program_id, &players[3].pubkey(), &game);
+    let result = env.process(&[early], &[&players[3]]).await;
+    assert_custom_error(result, RpsError::TimeoutNotReached);
+
+    env.advance_clock(1).await;
+    let late = instruction::reveal_choice(&env.program_id, &players[2].pubkey(), &game, Choice::SCISSORS, SALT);
+    let result = env.process(&[late], &[&players[2]]).await;
+    assert_custom_error(result, RpsError::RevealDeadlinePassed);
+
+    let timeout = instruction::resolve_timeout(&env.program_id, &players[3].pubkey(), &game);
+    let timeout = env.with_human_profiles(timeout, &game).await;
+    env.process(&[timeout], &[&players[3]]).await.unwrap();
+
+    // The host's Rock beat the only other revealed Scissors, so the host takes the pot
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert_eq!(state.players[0].payout, 3 * ENTRY_FEE);
+}
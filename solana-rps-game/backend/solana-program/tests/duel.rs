@@ -0,0 +1,163 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    error::RpsError,
+    instruction::{self, GameSettings},
+    scoring::ScoringModel,
+    Choice, GameState,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+
+fn duel(best_of: u8, opponent: Pubkey) -> GameSettings {
+    GameSettings {
+        min_players: 2,
+        max_players: 2,
+        total_rounds: best_of,
+        game_mode: 3,
+        opponent,
+        ..game_settings(ENTRY_FEE)
+    }
+}
+
+async fn create_duel(env: &mut TestEnv, host: &Keypair, best_of: u8, opponent: Pubkey) -> Pubkey {
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = Keypair::new();
+    let initialize = env.initialize_game(host, &game, duel(best_of, opponent));
+    env.process(&[initialize], &[host, &game]).await.unwrap();
+    game.pubkey()
+}
+
+#[tokio::test]
+async fn first_to_a_majority_wins_and_draws_are_replayed() {
+    let (mut env, players) = start(2).await;
+    let game = create_duel(&mut env, &players[0], 3, Pubkey::default()).await;
+    env.join_sol_game(&players[1], &game).await;
+    assert_eq!(env.game(&game).await.state, GameState::CommitPhase);
+
+    let both: Vec<&Keypair> = players.iter().collect();
+
+    // A drawn round does not count towards the best of three
+    env.play_round(&game, &both, &[Choice::ROCK, Choice::ROCK]).await;
+    env.play_round(&game, &both, &[Choice::PAPER, Choice::ROCK]).await;
+    env.play_round(&game, &both, &[Choice::PAPER, Choice::SCISSORS]).await;
+
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::CommitPhase);
+    assert_eq!(state.current_round, 4);
+
+    env.play_round(&game, &both, &[Choice::SCISSORS, Choice::PAPER]).await;
+
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert_eq!(state.players[0].score, 2);
+    assert_eq!(state.players[0].payout, 2 * ENTRY_FEE);
+    assert_eq!(state.players[1].payout, 0);
+}
+
+#[tokio::test]
+async fn named_opponent_is_the_only_one_who_can_join() {
+    let (mut env, players) = start(3).await;
+    let game = create_duel(&mut env, &players[0], 1, players[1].pubkey()).await;
+
+    let join = instruction::join_game(&env.program_id, &players[2].pubkey(), &game, None);
+    assert_custom_error(env.process(&[join], &[&players[2]]).await, RpsError::NotInvitedOpponent);
+
+    // Not even the host can hand the opponent's seat to a bot
+    let add_bot = instruction::add_bot_players(&env.program_id, &players[0].pubkey(), &game, None, 1);
+    assert_custom_error(env.process(&[add_bot], &[&players[0]]).await, RpsError::NotInvitedOpponent);
+
+    // The duel starts as soon as the opponent takes their seat
+    env.join_sol_game(&players[1], &game).await;
+    assert_eq!(env.game(&game).await.state, GameState::CommitPhase);
+}
+
+#[tokio::test]
+async fn player_who_never_commits_forfeits() {
+    let (mut env, players) = start(2).await;
+    let game = create_duel(&mut env, &players[0], 3, Pubkey::default()).await;
+    env.join_sol_game(&players[1], &game).await;
+
+    let commitment = compute_commitment(&game, 1, &players[0].pubkey(), choice_byte(&Choice::ROCK).unwrap(), &[7u8; 32]);
+    let commit = instruction::commit_choice(&env.program_id, &players[0].pubkey(), &game, commitment);
+    env.process(&[commit], &[&players[0]]).await.unwrap();
+
+    env.advance_clock(61).await;
+    let resolve = instruction::resolve_timeout(&env.program_id, &env.payer(), &game);
+    let resolve = env.with_human_profiles(resolve, &game).await;
+    env.process(&[resolve], &[]).await.unwrap();
+
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert!(state.players[1].eliminated);
+    assert_eq!(state.players[0].payout, 2 * ENTRY_FEE);
+    assert_eq!(state.players[1].payout, 0);
+}
+
+#[tokio::test]
+async fn forfeiting_a_rematch_keeps_an_unclaimed_payout() {
+    let (mut env, players) = start(2).await;
+    let game = create_duel(&mut env, &players[0], 1, Pubkey::default()).await;
+    env.join_sol_game(&players[1], &game).await;
+
+    let both: Vec<&Keypair> = players.iter().collect();
+    env.play_round(&game, &both, &[Choice::ROCK, Choice::SCISSORS]).await;
+    assert_eq!(env.game(&game).await.players[0].payout, 2 * ENTRY_FEE);
+
+    // The winner leaves their payout unclaimed and sits the rematch out
+    let rematch = instruction::start_new_game_round(&env.program_id, &players[1].pubkey(), &game);
+    env.process(&[rematch], &[&players[1]]).await.unwrap();
+    let commitment = compute_commitment(&game, 1, &players[1].pubkey(), choice_byte(&Choice::ROCK).unwrap(), &[7u8; 32]);
+    let commit = instruction::commit_choice(&env.program_id, &players[1].pubkey(), &game, commitment);
+    env.process(&[commit], &[&players[1]]).await.unwrap();
+
+    env.advance_clock(61).await;
+    let resolve = instruction::resolve_timeout(&env.program_id, &env.payer(), &game);
+    let resolve = env.with_human_profiles(resolve, &game).await;
+    env.process(&[resolve], &[]).await.unwrap();
+
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert!(state.players[0].eliminated);
+    assert_eq!(state.players[0].payout, 2 * ENTRY_FEE);
+    assert_eq!(state.escrowed_total(), 2 * ENTRY_FEE);
+
+    let before = env.lamports(&players[0].pubkey()).await;
+    let claim = instruction::claim_winnings(&env.program_id, &players[0].pubkey(), &game, None);
+    env.process(&[claim], &[&players[0]]).await.unwrap();
+    assert_eq!(env.lamports(&players[0].pubkey()).await, before + 2 * ENTRY_FEE);
+}
+
+#[tokio::test]
+async fn duel_settings_are_validated() {
+    let (mut env, players) = start(2).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    // Best of an even number of rounds has no majority
+    let game = Keypair::new();
+    let even = env.initialize_game(&players[0], &game, duel(2, Pubkey::default()));
+    assert_custom_error(env.process(&[even], &[&players[0], &game]).await, RpsError::InvalidTotalRounds);
+
+    // Only duels reserve a seat, and never for the host
+    let game = Keypair::new();
+    let settings = GameSettings { opponent: players[1].pubkey(), ..game_settings(ENTRY_FEE) };
+    let manual = env.initialize_game(&players[0], &game, settings);
+    assert_custom_error(env.process(&[manual], &[&players[0], &game]).await, RpsError::InvalidOpponent);
+
+    let game = Keypair::new();
+    let own = env.initialize_game(&players[0], &game, duel(1, players[0].pubkey()));
+    assert_custom_error(env.process(&[own], &[&players[0], &game]).await, RpsError::InvalidOpponent);
+
+    // Two players never produce a unique majority to score against
+    let game = Keypair::new();
+    let settings = GameSettings { scoring_model: ScoringModel::MajorityLoses, ..duel(1, Pubkey::default()) };
+    let unscorable = env.initialize_game(&players[0], &game, settings);
+    assert_custom_error(env.process(&[unscorable], &[&players[0], &game]).await, RpsError::InvalidDuelScoring);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::{assert_custom_error, start, TestEnv, PLAYER_LAMPORTS};
+use solana_program_test::tokio;
+use solana_rps_game::{error::RpsError, find_vault_address, instruction, Choice, GameState};
+use solana_sdk::{
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Signer,
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+const FEE_BASIS_POINTS: u16 = 500;
+const TIMEOUT: u64 = 60;
+
+// Everything the game says it owes must be held by its vault, above rent
+async fn assert_escrow_matches(env: &mut TestEnv, game: &Pubkey) {
+    let (vault, _) = find_vault_address(game, &env.program_id);
+    let escrowed = env.lamports(&vault).await - Rent::default().minimum_balance(0);
+    assert_eq!(env.game(game).await.escrowed_total(), escrowed);
+}
+
+#[tokio::test]
+async fn bot_seats_are_funded_and_pay_their_funder() {
+    // Host, one human opponent, the bot funder and the treasury
+    let (mut env, wallets) = start(4).await;
+    let (host, opponent, funder, treasury) = (&wallets[0], &wallets[1], &wallets[2], &wallets[3]);
+    env.initialize_config(FEE_BASIS_POINTS, treasury.pubkey(), 86_400).await;
+
+    let (game, _) = env.create_sol_game(host, ENTRY_FEE, TIMEOUT).await;
+    assert_escrow_matches(&mut env, &game).await;
+    env.join_sol_game(opponent, &game).await;
+    assert_escrow_matches(&mut env, &game).await;
+
+    // A wallet outside the game pays for the bot's seat
+    let add_bot = instruction::add_bot_players(&env.program_id, &funder.pubkey(), &game, None, 1);
+    env.process(&[add_bot], &[funder]).await.unwrap();
+    assert_eq!(env.lamports(&funder.pubkey()).await, PLAYER_LAMPORTS - ENTRY_FEE);
+    assert_eq!(env.game(&game).await.state, GameState::CommitPhase);
+    assert_escrow_matches(&mut env, &game).await;
+
+    let humans = [host, opponent];
+    env.play_round(&game, &humans, &[Choice::ROCK, Choice::ROCK]).await;
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert_escrow_matches(&mut env, &game).await;
+
+    // Claims drain the ledger without breaking the invariant; the bot's share
+    // goes to its funder
+    let bot_payout = state.players[2].payout;
+    for wallet in [host, opponent, funder] {
+        let balance = env.lamports(&wallet.pubkey()).await;
+        let owed: u64 = state.players.iter().filter(|p| p.is_owned_by(&wallet.pubkey())).map(|p| p.payout).sum();
+
+        let claim = instruction::claim_winnings(&env.program_id, &wallet.pubkey(), &game, None);
+        let result = env.process(&[claim], &[wallet]).await;
+        if owed == 0 {
+            assert_custom_error(result, RpsError::NotAWinner);
+        } else {
+            result.unwrap();
+        }
+        assert_eq!(env.lamports(&wallet.pubkey()).await, balance + owed);
+        assert_escrow_matches(&mut env, &game).await;
+    }
+    assert_eq!(
+        env.lamports(&funder.pubkey()).await,
+        PLAYER_LAMPORTS - ENTRY_FEE + bot_payout
+    );
+
+    let withdraw = instruction::withdraw_fees(&env.program_id, &treasury.pubkey(), &game, &treasury.pubkey(), None);
+    env.process(&[withdraw], &[treasury]).await.unwrap();
+    assert_escrow_matches(&mut env, &game).await;
+    assert!(env.game(&game).await.payouts_complete());
+}
+
+#[tokio::test]
+async fn bot_funder_is_refunded_when_the_lobby_is_cancelled() {
+    let (mut env, wallets) = start(2).await;
+    let (host, funder) = (&wallets[0], &wallets[1]);
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    let (game, _) = env.create_sol_game(host, ENTRY_FEE, TIMEOUT).await;
+    let add_bot = instruction::add_bot_players(&env.program_id, &funder.pubkey(), &game, None, 1);
+    env.process(&[add_bot], &[funder]).await.unwrap();
+    assert_escrow_matches(&mut env, &game).await;
+
+    env.advance_clock(TIMEOUT as i64).await;
+    let timeout = instruction::resolve_timeout(&env.program_id, &funder.pubkey(), &game);
+    env.process(&[timeout], &[funder]).await.unwrap();
+
+    let refund = instruction::claim_refund(&env.program_id, &funder.pubkey(), &game, None);
+    env.process(&[refund], &[funder]).await.unwrap();
+    assert_eq!(env.lamports(&funder.pubkey()).await, PLAYER_LAMPORTS);
+    assert_escrow_matches(&mut env, &game).await;
+
+    let refund = instruction::claim_refund(&env.program_id, &funder.pubkey(), &game, None);
+    let result = env.process(&[refund], &[funder]).await;
+    assert_custom_error(result, RpsError::AlreadyClaimed);
+
+    let refund = instruction::claim_refund(&env.program_id, &host.pubkey(), &game, None);
+    env.process(&[refund], &[host]).await.unwrap();
+    assert_escrow_matches(&mut env, &game).await;
+    assert_eq!(env.game(&game).await.game_pot, 0);
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::BorshSerialize;
+use common::{game_settings, setup};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    events::{parse_logs, GameEvent, Payout, SeatResult, EVENT_DISCRIMINATOR, EVENT_VERSION},
+    instruction,
+    Choice, CurrencyMode, GameMode,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+
+fn data_line(fields: &[&[u8]]) -> String {
+    let encoded: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+    format!("Program data: {}", encoded.join(" "))
+}
+
+#[test]
+fn decoding_skips_other_programs_and_versions() {
+    let program_id = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let event = GameEvent::Claimed { game: Pubkey::new_unique(), player: Pubkey::new_unique(), amount: 5 };
+    let payload = event.try_to_vec().unwrap();
+
+    let logs = vec![
+        format!("Program {} invoke [1]", program_id),
+        "Program log: success".to_string(),
+        data_line(&[EVENT_DISCRIMINATOR, &[EVENT_VERSION], &payload]),
+        // Data logged by a program this one calls is not ours, even with our layout
+        format!("Program {} invoke [2]", other),
+        data_line(&[EVENT_DISCRIMINATOR, &[EVENT_VERSION], &payload]),
+        format!("Program {} success", other),
+        data_line(&[EVENT_DISCRIMINATOR, &[EVENT_VERSION + 1], &payload]),
+        data_line(&[b"another!", &[EVENT_VERSION], &payload]),
+        data_line(&[EVENT_DISCRIMINATOR, &[EVENT_VERSION], &payload[..10]]),
+        format!("Program {} success", program_id),
+        data_line(&[EVENT_DISCRIMINATOR, &[EVENT_VERSION], &payload]),
+    ];
+
+    assert_eq!(parse_logs(&program_id, &logs), vec![event]);
+}
+
+#[tokio::test]
+async fn a_game_timeline_can_be_rebuilt_from_logs() {
+    let (mut env, players) = setup(3).await;
+    let program_id = env.program_id;
+    let wallets: Vec<Pubkey> = players.iter().map(|p| p.pubkey()).collect();
+
+    let game = Keypair::new();
+    let create = env.initialize_game(&players[0], &game, game_settings(ENTRY_FEE));
+    let mut logs = env.process_logged(&[create], &[&players[0], &game]).await;
+    let game = game.pubkey();
+
+    for player in &players[1..] {
+        let join = instruction::join_game(&program_id, &player.pubkey(), &game, None);
+        logs.extend(env.process_logged(&[join], &[player]).await);
+    }
+
+    let choices = [Choice::ROCK, Choice::SCISSORS, Choice::SCISSORS];
+    let salt = [7u8; 32];
+    for (player, choice) in players.iter().zip(&choices) {
+        let commitment = compute_commitment(&game, 1, &player.pubkey(), choice_byte(choice).unwrap(), &salt);
+        let commit = instruction::commit_choice(&program_id, &player.pubkey(), &game, commitment);
+        logs.extend(env.process_logged(&[commit], &[player]).await);
+    }
+    for (player, choice) in players.iter().zip(&choices) {
+        let reveal = instruction::reveal_choice(&program_id, &player.pubkey(), &game, *choice, salt);
+        let reveal = instruction::with_profiles(reveal, &wallets);
+        logs.extend(env.process_logged(&[reveal], &[player]).await);
+    }
+
+    let claim = instruction::claim_winnings(&program_id, &wallets[0], &game, None);
+    logs.extend(env.process_logged(&[claim], &[&players[0]]).await);
+
+    let joined = |player: Pubkey| GameEvent::PlayerJoined { game, player, is_bot: false };
+    let committed = |player: Pubkey| GameEvent::Committed { game, player, round: 1 };
+    let revealed = |player: Pubkey, choice: Choice| GameEvent::Revealed { game, player, round: 1, choice };
+    let seat = |player: Pubkey, choice: Choice, score: u8| SeatResult { player, choice, score, eliminated: false };
+
+    let mut expected = vec![
+        GameEvent::GameCreated {
+            game,
+            host: wallets[0],
+            currency_mode: CurrencyMode::SOL,
+            game_mode: GameMode::Manual,
+            entry_fee: ENTRY_FEE,
+            min_players: 3,
+            max_players: 3,
+            total_rounds: 1,
+        },
+    ];
+    expected.extend(wallets.iter().map(|w| joined(*w)));
+    expected.extend(wallets.iter().map(|w| committed(*w)));
+    expected.extend(wallets.iter().zip(choices).map(|(w, c)| revealed(*w, c)));
+    expected.extend([
+        GameEvent::RoundResolved {
+            game,
+            round: 1,
+            seats: vec![
+                seat(wallets[0], Choice::ROCK, 2),
+                seat(wallets[1], Choice::SCISSORS, 0),
+                seat(wallets[2], Choice::SCISSORS, 0),
+            ],
+        },
+        GameEvent::GameFinished { game, payouts: vec![Payout { player: wallets[0], amount: 3 * ENTRY_FEE }] },
+        GameEvent::Claimed { game, player: wallets[0], amount: 3 * ENTRY_FEE },
+    ]);
+
+    assert_eq!(parse_logs(&program_id, &logs), expected);
+}
+
+#[tokio::test]
+async fn refunds_of_cancelled_lobbies_are_logged() {
+    let (mut env, players) = setup(1).await;
+    let (game, _) = env.create_sol_game(&players[0], ENTRY_FEE, 60).await;
+
+    env.advance_clock(61).await;
+    let resolve = instruction::resolve_timeout(&env.program_id, &players[0].pubkey(), &game);
+    env.process(&[resolve], &[&players[0]]).await.unwrap();
+
+    let refund = instruction::claim_refund(&env.program_id, &players[0].pubkey(), &game, None);
+    let logs = env.process_logged(&[refund], &[&players[0]]).await;
+
+    assert_eq!(
+        parse_logs(&env.program_id, &logs),
+        vec![GameEvent::Refunded { game, player: players[0].pubkey(), amount: ENTRY_FEE }],
+    );
+}
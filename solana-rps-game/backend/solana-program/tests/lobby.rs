@@ -0,0 +1,141 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::{assert_custom_error, game_settings, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    error::RpsError,
+    instruction::{self, GameSettings},
+    lobby::{find_lobby_address, Lobby, LobbyFilter, LISTING_DEPOSIT, MAX_LISTED_TIMEOUT},
+    CurrencyMode, GameMode, GameState,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+async fn lobby(env: &mut TestEnv) -> Lobby {
+    let (address, _) = find_lobby_address(&env.program_id);
+    let account = env.context.banks_client.get_account(address).await.unwrap().unwrap();
+    Lobby::deserialize(&mut &account.data[..]).unwrap()
+}
+
+async fn setup(player_count: usize) -> (TestEnv, Vec<Keypair>) {
+    let (mut env, players) = start(player_count).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let create = instruction::initialize_lobby(&env.program_id, &env.payer());
+    env.process(&[create], &[]).await.unwrap();
+    (env, players)
+}
+
+// Host a listed three player SOL game
+async fn create_listed_game(env: &mut TestEnv, host: &Keypair, entry_fee: u64, game_mode: u8) -> Pubkey {
+    let game = Keypair::new();
+    let survivors = if game_mode == 2 { 1 } else { 0 };
+    let settings = GameSettings {
+        game_mode,
+        survivors,
+        ..game_settings(entry_fee)
+    };
+    let initialize = env.initialize_game(host, &game, settings);
+    env.process(&[instruction::with_lobby(initialize)], &[host, &game]).await.unwrap();
+    game.pubkey()
+}
+
+#[tokio::test]
+async fn open_games_can_be_filtered_from_one_fetch() {
+    let (mut env, players) = setup(1).await;
+    let cheap = create_listed_game(&mut env, &players[0], 1_000_000, 0).await;
+    let pricey = create_listed_game(&mut env, &players[0], 5_000_000, 0).await;
+    let royale = create_listed_game(&mut env, &players[0], 1_000_000, 2).await;
+
+    let lobby = lobby(&mut env).await;
+    let listed = |filter: LobbyFilter| lobby.open_games(&filter).map(|e| e.game).collect::<Vec<_>>();
+
+    assert_eq!(listed(LobbyFilter::default()), vec![cheap, pricey, royale]);
+    assert_eq!(
+        listed(LobbyFilter { max_entry_fee: Some(2_000_000), ..LobbyFilter::default() }),
+        vec![cheap, royale],
+    );
+    assert_eq!(
+        listed(LobbyFilter { game_mode: Some(GameMode::BattleRoyale), ..LobbyFilter::default() }),
+        vec![royale],
+    );
+    assert_eq!(
+        listed(LobbyFilter { currency_mode: Some(CurrencyMode::RPSToken), ..LobbyFilter::default() }),
+        Vec::<Pubkey>::new(),
+    );
+}
+
+#[tokio::test]
+async fn games_leave_the_lobby_when_they_fill_up() {
+    let (mut env, players) = setup(3).await;
+    let game = create_listed_game(&mut env, &players[0], 1_000_000, 0).await;
+
+    let join = instruction::join_game(&env.program_id, &players[1].pubkey(), &game, None);
+    env.process(&[instruction::with_lobby(join)], &[&players[1]]).await.unwrap();
+    assert_eq!(lobby(&mut env).await.games.len(), 1);
+
+    let join = instruction::join_game(&env.program_id, &players[2].pubkey(), &game, None);
+    env.process(&[instruction::with_lobby(join)], &[&players[2]]).await.unwrap();
+    assert_eq!(env.game(&game).await.state, GameState::CommitPhase);
+    assert!(lobby(&mut env).await.games.is_empty());
+}
+
+#[tokio::test]
+async fn cancelled_lobbies_are_delisted() {
+    let (mut env, players) = setup(1).await;
+    let game = create_listed_game(&mut env, &players[0], 1_000_000, 0).await;
+
+    env.advance_clock(61).await;
+    let resolve = instruction::resolve_timeout(&env.program_id, &players[0].pubkey(), &game);
+    env.process(&[instruction::with_lobby(resolve)], &[&players[0]]).await.unwrap();
+
+    assert_eq!(env.game(&game).await.state, GameState::Cancelled);
+    assert!(lobby(&mut env).await.games.is_empty());
+}
+
+#[tokio::test]
+async fn pruning_drops_games_that_left_the_lobby_without_it() {
+    let (mut env, players) = setup(3).await;
+    let started = create_listed_game(&mut env, &players[0], 1_000_000, 0).await;
+    let open = create_listed_game(&mut env, &players[0], 1_000_000, 0).await;
+
+    // Joined without passing the registry, so the full game stays listed
+    env.join_sol_game(&players[1], &started).await;
+    env.join_sol_game(&players[2], &started).await;
+    assert_eq!(lobby(&mut env).await.games.len(), 2);
+
+    let prune = instruction::prune_lobby(&env.program_id, &[started, open]);
+    env.process(&[prune], &[]).await.unwrap();
+
+    let listed: Vec<Pubkey> = lobby(&mut env).await.games.iter().map(|e| e.game).collect();
+    assert_eq!(listed, vec![open]);
+}
+
+#[tokio::test]
+async fn listings_cost_a_deposit_and_expire_with_their_lobby() {
+    let (mut env, players) = setup(1).await;
+
+    let game = Keypair::new();
+    let settings = GameSettings { timeout_seconds: MAX_LISTED_TIMEOUT + 1, ..game_settings(1_000_000) };
+    let initialize = env.initialize_game(&players[0], &game, settings);
+    let result = env.process(&[instruction::with_lobby(initialize)], &[&players[0], &game]).await;
+    assert_custom_error(result, RpsError::ListedTimeoutTooLong);
+
+    let game = create_listed_game(&mut env, &players[0], 1_000_000, 0).await;
+    let account = env.context.banks_client.get_account(game).await.unwrap().unwrap();
+    let rent = env.context.banks_client.get_rent().await.unwrap().minimum_balance(account.data.len());
+    assert_eq!(account.lamports, rent + LISTING_DEPOSIT);
+
+    // Nobody resolved the timed-out lobby, but it can still be pruned
+    let prune = instruction::prune_lobby(&env.program_id, &[game]);
+    env.process(&[prune], &[]).await.unwrap();
+    assert_eq!(lobby(&mut env).await.games.len(), 1);
+
+    env.advance_clock(61).await;
+    let prune = instruction::prune_lobby(&env.program_id, &[game]);
+    env.process(&[prune], &[]).await.unwrap();
+    assert!(lobby(&mut env).await.games.is_empty());
+    assert_eq!(env.game(&game).await.state, GameState::WaitingForPlayers);
+}
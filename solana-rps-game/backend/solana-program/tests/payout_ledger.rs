@@ -0,0 +1,60 @@
+mod common;
+
+use common::{assert_custom_error, start};
+use solana_program_test::tokio;
+use solana_rps_game::{error::RpsError, instruction, Choice, GameState};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+// Odd entry fee so the split between two winners leaves dust in the pot
+const ENTRY_FEE: u64 = 1_000_001;
+
+#[tokio::test]
+async fn tied_winners_get_frozen_shares_in_any_order() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    let (game, _) = env.create_sol_game(&players[0], ENTRY_FEE, 60).await;
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    // Two Rocks beat one Scissors, so the first two players tie for the win
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.play_round(&game, &signers, &[Choice::ROCK, Choice::ROCK, Choice::SCISSORS]).await;
+
+    let pot = 3 * ENTRY_FEE;
+    let share = pot / 2;
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert_eq!(state.players[0].payout, share);
+    assert_eq!(state.players[1].payout, share);
+    assert_eq!(state.players[2].payout, 0);
+    assert_eq!(state.game_pot, pot - 2 * share);
+
+    // The second winner claims first and still gets exactly one share
+    let balances = [
+        env.lamports(&players[0].pubkey()).await,
+        env.lamports(&players[1].pubkey()).await,
+    ];
+    for index in [1, 0] {
+        let claim = instruction::claim_winnings(&env.program_id, &players[index].pubkey(), &game, None);
+        env.process(&[claim], &[&players[index]]).await.unwrap();
+        assert_eq!(env.lamports(&players[index].pubkey()).await, balances[index] + share);
+    }
+
+    // Repeating a claim changes nothing
+    let repeated = instruction::claim_winnings(&env.program_id, &players[1].pubkey(), &game, None);
+    let result = env.process(&[repeated], &[&players[1]]).await;
+    assert_custom_error(result, RpsError::AlreadyClaimed);
+    assert_eq!(env.lamports(&players[1].pubkey()).await, balances[1] + share);
+
+    let losing = instruction::claim_winnings(&env.program_id, &players[2].pubkey(), &game, None);
+    let result = env.process(&[losing], &[&players[2]]).await;
+    assert_custom_error(result, RpsError::NotAWinner);
+
+    let state = env.game(&game).await;
+    assert!(state.players.iter().all(|p| p.payout == 0));
+    assert!(state.payouts_complete());
+}
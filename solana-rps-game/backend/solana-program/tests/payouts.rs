@@ -0,0 +1,104 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, start};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    error::RpsError,
+    instruction::{self, GameSettings},
+    payouts::PayoutTable,
+    Choice, GameState,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+const SEVENTY_THIRTY: PayoutTable = PayoutTable::TopTwo { first_bps: 7_000, second_bps: 3_000 };
+
+#[test]
+fn winner_take_all_splits_ties_evenly() {
+    let table = PayoutTable::WinnerTakeAll;
+    assert_eq!(table.split(900, &[Some(3), Some(1), Some(1)]), vec![900, 0, 0]);
+    assert_eq!(table.split(900, &[Some(2), Some(2), Some(0)]), vec![450, 450, 0]);
+
+    // Dust from an uneven split is left over
+    assert_eq!(table.split(1_000, &[Some(1), Some(1), Some(1)]), vec![333, 333, 333]);
+}
+
+#[test]
+fn top_two_pays_by_rank_and_pools_shares_of_tied_places() {
+    assert_eq!(SEVENTY_THIRTY.split(1_000, &[Some(3), Some(1), Some(0)]), vec![700, 300, 0]);
+
+    // Seat order does not matter
+    assert_eq!(SEVENTY_THIRTY.split(1_000, &[Some(1), Some(0), Some(3)]), vec![300, 0, 700]);
+
+    // Tied players share the places they cover
+    assert_eq!(SEVENTY_THIRTY.split(1_000, &[Some(3), Some(3), Some(0)]), vec![500, 500, 0]);
+    assert_eq!(SEVENTY_THIRTY.split(1_000, &[Some(3), Some(1), Some(1)]), vec![700, 150, 150]);
+    assert_eq!(SEVENTY_THIRTY.split(900, &[Some(2), Some(2), Some(2)]), vec![300, 300, 300]);
+
+    // A place nobody reached goes to first place
+    assert_eq!(SEVENTY_THIRTY.split(1_000, &[Some(2), None]), vec![1_000, 0]);
+}
+
+#[test]
+fn proportional_follows_score() {
+    let table = PayoutTable::ProportionalToScore;
+    assert_eq!(table.split(1_000, &[Some(3), Some(1), Some(0)]), vec![750, 250, 0]);
+    assert_eq!(table.split(1_000, &[Some(3), None, Some(1)]), vec![750, 0, 250]);
+
+    // Nobody scored: everyone still in shares equally
+    assert_eq!(table.split(1_000, &[Some(0), Some(0), None]), vec![500, 500, 0]);
+}
+
+#[test]
+fn top_two_shares_must_cover_the_pot_highest_first() {
+    assert!(SEVENTY_THIRTY.validate().is_ok());
+    assert!(PayoutTable::TopTwo { first_bps: 10_000, second_bps: 0 }.validate().is_ok());
+    assert!(PayoutTable::TopTwo { first_bps: 6_000, second_bps: 3_000 }.validate().is_err());
+    assert!(PayoutTable::TopTwo { first_bps: 3_000, second_bps: 7_000 }.validate().is_err());
+    assert!(PayoutTable::TopTwo { first_bps: u16::MAX, second_bps: 0 }.validate().is_err());
+}
+
+#[tokio::test]
+async fn settlement_follows_the_payout_table() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    let game = Keypair::new();
+    let payout_table = SEVENTY_THIRTY;
+    let initialize = env.initialize_game(&players[0], &game, GameSettings { payout_table, ..game_settings(ENTRY_FEE) });
+    env.process(&[initialize], &[&players[0], &game]).await.unwrap();
+    let game = game.pubkey();
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    // The two Scissors tie for second place and split its share
+    let all: Vec<&Keypair> = players.iter().collect();
+    env.play_round(&game, &all, &[Choice::ROCK, Choice::SCISSORS, Choice::SCISSORS]).await;
+
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert_eq!(state.players[0].payout, 2_100_000);
+    assert_eq!(state.players[1].payout, 450_000);
+    assert_eq!(state.players[2].payout, 450_000);
+    assert_eq!(state.game_pot, 0);
+
+    // Runners-up claim like any winner
+    let before = env.lamports(&players[1].pubkey()).await;
+    let claim = instruction::claim_winnings(&env.program_id, &players[1].pubkey(), &game, None);
+    env.process(&[claim], &[&players[1]]).await.unwrap();
+    assert_eq!(env.lamports(&players[1].pubkey()).await - before, 450_000);
+}
+
+#[tokio::test]
+async fn invalid_payout_tables_are_rejected_at_creation() {
+    let (mut env, players) = start(1).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    let game = Keypair::new();
+    let payout_table = PayoutTable::TopTwo { first_bps: 5_000, second_bps: 4_000 };
+    let initialize = env.initialize_game(&players[0], &game, GameSettings { payout_table, ..game_settings(ENTRY_FEE) });
+    assert_custom_error(env.process(&[initialize], &[&players[0], &game]).await, RpsError::InvalidPayoutTable);
+}
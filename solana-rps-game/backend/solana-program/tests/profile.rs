@@ -0,0 +1,208 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::{assert_custom_error, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    error::RpsError,
+    instruction,
+    profile::{find_profile_address, PlayerProfile},
+    Choice, GameState,
+};
+use solana_sdk::{
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+
+async fn profile(env: &mut TestEnv, wallet: &Pubkey) -> PlayerProfile {
+    let (address, _) = find_profile_address(wallet, &env.program_id);
+    let account = env.context.banks_client.get_account(address).await.unwrap().unwrap();
+    PlayerProfile::deserialize(&mut &account.data[..]).unwrap()
+}
+
+async fn create_profiles(env: &mut TestEnv, players: &[Keypair]) {
+    for player in players {
+        let create = instruction::create_player_profile(&env.program_id, &player.pubkey());
+        env.process(&[create], &[player]).await.unwrap();
+    }
+}
+
+// Like `TestEnv::play_round`, but every reveal passes the profiles of `wallets`
+async fn play_round_with_profiles(
+    env: &mut TestEnv,
+    game: &Pubkey,
+    players: &[Keypair],
+    choices: &[Choice],
+    wallets: &[Pubkey],
+) {
+    let salt = [9u8; 32];
+    let round = env.game(game).await.current_round;
+    for (player, choice) in players.iter().zip(choices) {
+        let commitment = compute_commitment(game, round, &player.pubkey(), choice_byte(choice).unwrap(), &salt);
+        let commit = instruction::commit_choice(&env.program_id, &player.pubkey(), game, commitment);
+        env.process(&[commit], &[player]).await.unwrap();
+    }
+
+    for (player, choice) in players.iter().zip(choices) {
+        let reveal = instruction::reveal_choice(&env.program_id, &player.pubkey(), game, *choice, salt);
+        env.process(&[instruction::with_profiles(reveal, wallets)], &[player]).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn settled_games_are_recorded_in_every_passed_profile() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    create_profiles(&mut env, &players).await;
+
+    let (game, _) = env.create_sol_game(&players[0], ENTRY_FEE, 60).await;
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    // Every reveal passes every profile; only the revealer's choice is counted
+    // and the game is recorded once, by the reveal that settles it
+    let wallets: Vec<Pubkey> = players.iter().map(|p| p.pubkey()).collect();
+    let choices = [Choice::ROCK, Choice::SCISSORS, Choice::SCISSORS];
+    play_round_with_profiles(&mut env, &game, &players, &choices, &wallets).await;
+    assert_eq!(env.game(&game).await.state, GameState::Finished);
+
+    let winner = profile(&mut env, &wallets[0]).await;
+    assert_eq!(winner.wallet, wallets[0]);
+    assert_eq!(winner.games_played, 1);
+    assert_eq!(winner.rounds_won, 1);
+    assert_eq!(winner.choice_counts[Choice::ROCK.index() as usize - 1], 1);
+    assert_eq!(winner.choice_counts.iter().sum::<u64>(), 1);
+    assert_eq!(winner.total_wagered, ENTRY_FEE);
+    assert_eq!(winner.total_won, 3 * ENTRY_FEE);
+
+    let loser = profile(&mut env, &wallets[1]).await;
+    assert_eq!(loser.games_played, 1);
+    assert_eq!(loser.rounds_won, 0);
+    assert_eq!(loser.choice_counts[Choice::SCISSORS.index() as usize - 1], 1);
+    assert_eq!(loser.total_wagered, ENTRY_FEE);
+    assert_eq!(loser.total_won, 0);
+
+    // A restarted game collects no entry fees, so nothing more is wagered
+    let restart = instruction::start_new_game_round(&env.program_id, &wallets[0], &game);
+    env.process(&[restart], &[&players[0]]).await.unwrap();
+    let choices = [Choice::PAPER, Choice::ROCK, Choice::ROCK];
+    play_round_with_profiles(&mut env, &game, &players, &choices, &wallets).await;
+
+    let loser = profile(&mut env, &wallets[1]).await;
+    assert_eq!(loser.games_played, 2);
+    assert_eq!(loser.total_wagered, ENTRY_FEE);
+    assert_eq!(loser.choice_counts[Choice::ROCK.index() as usize - 1], 1);
+    assert_eq!(profile(&mut env, &wallets[0]).await.rounds_won, 2);
+}
+
+#[tokio::test]
+async fn timeouts_record_games_settled_without_every_reveal() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    create_profiles(&mut env, &players).await;
+
+    let (game, _) = env.create_sol_game(&players[0], ENTRY_FEE, 60).await;
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    let salt = [3u8; 32];
+    for player in &players {
+        let commitment = compute_commitment(&game, 1, &player.pubkey(), choice_byte(&Choice::PAPER).unwrap(), &salt);
+        let commit = instruction::commit_choice(&env.program_id, &player.pubkey(), &game, commitment);
+        env.process(&[commit], &[player]).await.unwrap();
+    }
+    let reveal = instruction::reveal_choice(&env.program_id, &players[0].pubkey(), &game, Choice::PAPER, salt);
+    env.process(&[reveal], &[&players[0]]).await.unwrap();
+
+    env.advance_clock(61).await;
+    let wallets: Vec<Pubkey> = players.iter().map(|p| p.pubkey()).collect();
+    let resolve = instruction::resolve_timeout(&env.program_id, &wallets[1], &game);
+    env.process(&[instruction::with_profiles(resolve, &wallets)], &[&players[1]]).await.unwrap();
+
+    // The only reveal beat both players who never revealed
+    let winner = profile(&mut env, &wallets[0]).await;
+    assert_eq!(winner.games_played, 1);
+    assert_eq!(winner.rounds_won, 1);
+    assert_eq!(winner.total_won, 3 * ENTRY_FEE);
+
+    // Unrevealed choices are not counted
+    let silent = profile(&mut env, &wallets[2]).await;
+    assert_eq!(silent.games_played, 1);
+    assert_eq!(silent.choice_counts.iter().sum::<u64>(), 0);
+}
+
+#[tokio::test]
+async fn only_profiles_of_the_game_may_be_passed_once() {
+    let (mut env, players) = start(4).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    create_profiles(&mut env, &players).await;
+
+    let (game, _) = env.create_sol_game(&players[0], ENTRY_FEE, 60).await;
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    let salt = [5u8; 32];
+    let commitment = compute_commitment(&game, 1, &players[0].pubkey(), choice_byte(&Choice::ROCK).unwrap(), &salt);
+    let commit = instruction::commit_choice(&env.program_id, &players[0].pubkey(), &game, commitment);
+    env.process(&[commit], &[&players[0]]).await.unwrap();
+    env.advance_clock(61).await;
+
+    let program_id = env.program_id;
+    let resolve = || instruction::resolve_timeout(&program_id, &players[0].pubkey(), &game);
+
+    // Counting a profile twice would inflate its stats
+    let twice = instruction::with_profiles(resolve(), &[players[0].pubkey(), players[0].pubkey()]);
+    assert_custom_error(env.process(&[twice], &[&players[0]]).await, RpsError::DuplicateProfile);
+
+    let outsider = instruction::with_profiles(resolve(), &[players[3].pubkey()]);
+    assert_custom_error(env.process(&[outsider], &[&players[0]]).await, RpsError::PlayerNotInGame);
+
+    let mut not_a_profile = resolve();
+    not_a_profile.accounts.push(AccountMeta::new(game, false));
+    assert_custom_error(env.process(&[not_a_profile], &[&players[0]]).await, RpsError::InvalidProfileAccount);
+}
+
+#[tokio::test]
+async fn settling_needs_every_human_profile() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    create_profiles(&mut env, &players[..2]).await;
+
+    let (game, _) = env.create_sol_game(&players[0], ENTRY_FEE, 60).await;
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    let salt = [4u8; 32];
+    let choices = [Choice::PAPER, Choice::ROCK, Choice::ROCK];
+    for (player, choice) in players.iter().zip(&choices) {
+        let commitment = compute_commitment(&game, 1, &player.pubkey(), choice_byte(choice).unwrap(), &salt);
+        let commit = instruction::commit_choice(&env.program_id, &player.pubkey(), &game, commitment);
+        env.process(&[commit], &[player]).await.unwrap();
+    }
+
+    // Reveals that don't settle the game need no profiles
+    for (player, choice) in players.iter().zip(&choices).take(2) {
+        let reveal = instruction::reveal_choice(&env.program_id, &player.pubkey(), &game, *choice, salt);
+        env.process(&[reveal], &[player]).await.unwrap();
+    }
+
+    // A loser's profile cannot be left out of the record
+    let wallets: Vec<Pubkey> = players.iter().map(|p| p.pubkey()).collect();
+    let program_id = env.program_id;
+    let reveal = || instruction::reveal_choice(&program_id, &wallets[2], &game, Choice::ROCK, salt);
+    let dodged = instruction::with_profiles(reveal(), &[wallets[0], wallets[2]]);
+    assert_custom_error(env.process(&[dodged], &[&players[2]]).await, RpsError::MissingProfile);
+
+    // The last player never created a profile and is passed by its address
+    let settle = instruction::with_profiles(reveal(), &wallets);
+    env.process(&[settle], &[&players[2]]).await.unwrap();
+    assert_eq!(env.game(&game).await.state, GameState::Finished);
+
+    let loser = profile(&mut env, &wallets[1]).await;
+    assert_eq!(loser.games_played, 1);
+    assert_eq!(loser.total_wagered, ENTRY_FEE);
+}
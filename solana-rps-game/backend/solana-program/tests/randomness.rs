@@ -0,0 +1,135 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    error::RpsError,
+    instruction::{self, GameSettings},
+    randomness::{count_seed_hash, GameRng},
+    GameState,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+const COUNT_SEED: [u8; 32] = [42u8; 32];
+
+// Host a lobby that starts with either three or four players
+async fn create_ranged_game(env: &mut TestEnv, host: &Keypair) -> Pubkey {
+    let game = Keypair::new();
+    let settings = GameSettings {
+        max_players: 4,
+        count_seed_hash: count_seed_hash(&game.pubkey(), &COUNT_SEED),
+        ..game_settings(ENTRY_FEE)
+    };
+    let initialize = env.initialize_game(host, &game, settings);
+    env.process(&[initialize], &[host, &game]).await.unwrap();
+    game.pubkey()
+}
+
+#[tokio::test]
+async fn player_count_is_drawn_from_the_host_seed_once_the_minimum_is_seated() {
+    let (mut env, players) = start(4).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = create_ranged_game(&mut env, &players[0]).await;
+    let program_id = env.program_id;
+    let reveal = |host: &Keypair, seed: [u8; 32]| instruction::reveal_count_seed(&program_id, &host.pubkey(), &game, seed);
+
+    // Every seat stays open until the draw, which has to wait for the minimum
+    env.join_sol_game(&players[1], &game).await;
+    let early = reveal(&players[0], COUNT_SEED);
+    assert_custom_error(env.process(&[early], &[&players[0]]).await, RpsError::CountSeedNotDue);
+
+    env.join_sol_game(&players[2], &game).await;
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::WaitingForPlayers);
+    assert_eq!(state.player_count, 4);
+
+    let not_host = reveal(&players[1], COUNT_SEED);
+    assert_custom_error(env.process(&[not_host], &[&players[1]]).await, RpsError::InvalidHost);
+    let wrong_seed = reveal(&players[0], [7u8; 32]);
+    assert_custom_error(env.process(&[wrong_seed], &[&players[0]]).await, RpsError::CountSeedMismatch);
+
+    env.process(&[reveal(&players[0], COUNT_SEED)], &[&players[0]]).await.unwrap();
+    let state = env.game(&game).await;
+    let drawn = 3 + GameRng::new(&state.entropy, &COUNT_SEED).below(2) as u8;
+    assert_eq!(state.player_count, drawn);
+    assert_eq!(state.count_seed_hash, [0; 32]);
+    match state.player_count {
+        3 => assert_eq!(state.state, GameState::CommitPhase),
+        4 => {
+            assert_eq!(state.state, GameState::WaitingForPlayers);
+            env.join_sol_game(&players[3], &game).await;
+            assert_eq!(env.game(&game).await.state, GameState::CommitPhase);
+        }
+        other => panic!("player count {} outside the lobby range", other),
+    }
+}
+
+#[tokio::test]
+async fn a_held_back_count_seed_only_lets_the_lobby_be_cancelled() {
+    let (mut env, players) = start(4).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    // The lobby waits an hour for players but the seed is due a minute after the minimum
+    let game = Keypair::new();
+    let settings = GameSettings {
+        max_players: 4,
+        timeout_seconds: 3_600,
+        count_seed_hash: count_seed_hash(&game.pubkey(), &COUNT_SEED),
+        ..game_settings(ENTRY_FEE)
+    };
+    let initialize = env.initialize_game(&players[0], &game, settings);
+    env.process(&[initialize], &[&players[0], &game]).await.unwrap();
+    let game = game.pubkey();
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    // Seats past the minimum stay closed until the count is drawn
+    let extra = instruction::join_game(&env.program_id, &players[3].pubkey(), &game, None);
+    assert_custom_error(env.process(&[extra], &[&players[3]]).await, RpsError::GameFull);
+
+    env.advance_clock(60).await;
+    let late = instruction::reveal_count_seed(&env.program_id, &players[0].pubkey(), &game, COUNT_SEED);
+    assert_custom_error(env.process(&[late], &[&players[0]]).await, RpsError::CountSeedDeadlinePassed);
+
+    let timeout = instruction::resolve_timeout(&env.program_id, &players[1].pubkey(), &game);
+    env.process(&[timeout], &[&players[1]]).await.unwrap();
+    assert_eq!(env.game(&game).await.state, GameState::Cancelled);
+}
+
+#[tokio::test]
+async fn only_lobbies_with_a_range_of_sizes_commit_to_a_count_seed() {
+    let (mut env, players) = start(1).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    let game = Keypair::new();
+    let unseeded = GameSettings { max_players: 4, ..game_settings(ENTRY_FEE) };
+    let initialize = env.initialize_game(&players[0], &game, unseeded);
+    assert_custom_error(env.process(&[initialize], &[&players[0], &game]).await, RpsError::InvalidCountSeedHash);
+
+    let fixed = GameSettings { count_seed_hash: count_seed_hash(&game.pubkey(), &COUNT_SEED), ..game_settings(ENTRY_FEE) };
+    let initialize = env.initialize_game(&players[0], &game, fixed);
+    assert_custom_error(env.process(&[initialize], &[&players[0], &game]).await, RpsError::InvalidCountSeedHash);
+}
+
+#[test]
+fn game_rng_is_deterministic_and_covers_its_range() {
+    let pool = [1u8; 32];
+    let slot_hash = [2u8; 32];
+
+    let draws: Vec<u64> = {
+        let mut rng = GameRng::new(&pool, &slot_hash);
+        (0..64).map(|_| rng.below(2)).collect()
+    };
+    let mut replay = GameRng::new(&pool, &slot_hash);
+    assert!(draws.iter().all(|draw| *draw == replay.below(2)));
+    assert!(draws.contains(&0) && draws.contains(&1));
+
+    // A different slot hash gives a different stream
+    let mut other = GameRng::new(&pool, &[3u8; 32]);
+    let mut rng = GameRng::new(&pool, &slot_hash);
+    assert_ne!(rng.next_u64(), other.next_u64());
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::{assert_custom_error, game_settings, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    error::RpsError,
+    instruction::{self, GameSettings},
+    profile::{find_profile_address, PlayerProfile},
+    rating::{apply_change, expected_score, rating_changes, INITIAL_RATING, MIN_RATING, SCORE_SCALE},
+    Choice,
+};
+use solana_sdk::{
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+
+#[test]
+fn expected_scores_follow_the_logistic_curve() {
+    assert_eq!(expected_score(0), SCORE_SCALE / 2);
+
+    // 400 points ahead is expected to score ten times as much as the opponent
+    assert_eq!(expected_score(400), 909_091);
+    assert_eq!(expected_score(-400), SCORE_SCALE - 909_091);
+
+    // Gaps between table entries are interpolated; huge gaps are capped
+    assert!(expected_score(10) > expected_score(0) && expected_score(10) < expected_score(25));
+    assert_eq!(expected_score(2_000), expected_score(800));
+    for gap in [1, 37, 333, 799, 5_000] {
+        assert_eq!(expected_score(gap) + expected_score(-gap), SCORE_SCALE);
+    }
+}
+
+#[test]
+fn two_player_changes_match_classic_elo() {
+    assert_eq!(rating_changes(&[1500, 1500], &[1, 0]), vec![16, -16]);
+    assert_eq!(rating_changes(&[1500, 1500], &[1, 1]), vec![0, 0]);
+
+    // The underdog gains more for an upset: 32 * (1 - 0.2403)
+    assert_eq!(rating_changes(&[1400, 1600], &[1, 0]), vec![24, -24]);
+    assert_eq!(rating_changes(&[1400, 1600], &[0, 1]), vec![-8, 8]);
+}
+
+#[test]
+fn multi_player_changes_share_k_over_every_opponent() {
+    // The winner beats both opponents; the two losers draw with each other
+    assert_eq!(rating_changes(&[1500, 1500, 1500], &[2, 0, 0]), vec![16, -8, -8]);
+    assert_eq!(rating_changes(&[1500, 1500, 1500, 1500], &[3, 2, 1, 0]), vec![16, 5, -5, -16]);
+
+    // Nobody to be rated against
+    assert_eq!(rating_changes(&[1500], &[0]), vec![0]);
+
+    assert_eq!(apply_change(MIN_RATING + 3, -16), MIN_RATING);
+    assert_eq!(apply_change(u16::MAX - 3, 16), u16::MAX);
+}
+
+async fn rating(env: &mut TestEnv, wallet: &Pubkey) -> u16 {
+    let (address, _) = find_profile_address(wallet, &env.program_id);
+    let account = env.context.banks_client.get_account(address).await.unwrap().unwrap();
+    PlayerProfile::deserialize(&mut &account.data[..]).unwrap().current_rating()
+}
+
+fn rating_band(min_rating: u16, max_rating: u16) -> GameSettings {
+    GameSettings { min_rating, max_rating, ..game_settings(ENTRY_FEE) }
+}
+
+async fn setup(player_count: usize) -> (TestEnv, Vec<Keypair>) {
+    let (mut env, players) = start(player_count).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    for player in &players {
+        let create = instruction::create_player_profile(&env.program_id, &player.pubkey());
+        env.process(&[create], &[player]).await.unwrap();
+    }
+    (env, players)
+}
+
+#[tokio::test]
+async fn settlement_rates_players_by_final_score() {
+    let (mut env, players) = setup(3).await;
+    let (game, _) = env.create_sol_game(&players[0], ENTRY_FEE, 60).await;
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    let wallets: Vec<Pubkey> = players.iter().map(|p| p.pubkey()).collect();
+    let choices = [Choice::ROCK, Choice::SCISSORS, Choice::SCISSORS];
+    let salt = [7u8; 32];
+    for (player, choice) in players.iter().zip(&choices) {
+        let commitment = compute_commitment(&game, 1, &player.pubkey(), choice_byte(choice).unwrap(), &salt);
+        let commit = instruction::commit_choice(&env.program_id, &player.pubkey(), &game, commitment);
+        env.process(&[commit], &[player]).await.unwrap();
+    }
+
+    // The last reveal settles the game and passes every profile
+    for (player, choice) in players.iter().zip(&choices) {
+        let reveal = instruction::reveal_choice(&env.program_id, &player.pubkey(), &game, *choice, salt);
+        env.process(&[instruction::with_profiles(reveal, &wallets)], &[player]).await.unwrap();
+    }
+
+    assert_eq!(rating(&mut env, &wallets[0]).await, INITIAL_RATING + 16);
+    assert_eq!(rating(&mut env, &wallets[1]).await, INITIAL_RATING - 8);
+    assert_eq!(rating(&mut env, &wallets[2]).await, INITIAL_RATING - 8);
+}
+
+#[tokio::test]
+async fn rated_lobbies_only_seat_players_within_the_band() {
+    let (mut env, players) = setup(3).await;
+
+    let game = Keypair::new();
+    let create = env.initialize_game(&players[0], &game, rating_band(1_400, 1_499));
+    env.process(&[create], &[&players[0], &game]).await.unwrap();
+    let game = game.pubkey();
+
+    let join = instruction::join_rated_game(&env.program_id, &players[1].pubkey(), &game, None);
+    assert_custom_error(env.process(&[join], &[&players[1]]).await, RpsError::RatingOutOfBand);
+
+    // A profile proves the rating of its own wallet only
+    let mut borrowed = instruction::join_game(&env.program_id, &players[1].pubkey(), &game, None);
+    let (host_profile, _) = find_profile_address(&players[0].pubkey(), &env.program_id);
+    borrowed.accounts.insert(4, AccountMeta::new_readonly(host_profile, false));
+    assert_custom_error(env.process(&[borrowed], &[&players[1]]).await, RpsError::InvalidProfileAccount);
+
+    let game = Keypair::new();
+    let create = env.initialize_game(&players[0], &game, rating_band(INITIAL_RATING, 0));
+    env.process(&[create], &[&players[0], &game]).await.unwrap();
+    let join = instruction::join_rated_game(&env.program_id, &players[1].pubkey(), &game.pubkey(), None);
+    env.process(&[join], &[&players[1]]).await.unwrap();
+}
+
+#[tokio::test]
+async fn inverted_rating_bands_are_rejected_at_creation() {
+    let (mut env, players) = setup(1).await;
+
+    let game = Keypair::new();
+    let initialize = env.initialize_game(&players[0], &game, rating_band(1_600, 1_400));
+    assert_custom_error(env.process(&[initialize], &[&players[0], &game]).await, RpsError::InvalidRatingBand);
+}
+
+#[tokio::test]
+async fn losers_cannot_dodge_their_rating_change() {
+    let (mut env, players) = setup(3).await;
+    let (game, _) = env.create_sol_game(&players[0], ENTRY_FEE, 60).await;
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    let salt = [7u8; 32];
+    for player in &players {
+        let commitment = compute_commitment(&game, 1, &player.pubkey(), choice_byte(&Choice::ROCK).unwrap(), &salt);
+        let commit = instruction::commit_choice(&env.program_id, &player.pubkey(), &game, commitment);
+        env.process(&[commit], &[player]).await.unwrap();
+    }
+    let reveal = instruction::reveal_choice(&env.program_id, &players[0].pubkey(), &game, Choice::ROCK, salt);
+    env.process(&[reveal], &[&players[0]]).await.unwrap();
+    env.advance_clock(61).await;
+
+    // The players who never revealed lose, and cannot settle without their own profiles
+    let wallets: Vec<Pubkey> = players.iter().map(|p| p.pubkey()).collect();
+    let resolve = instruction::resolve_timeout(&env.program_id, &wallets[1], &game);
+    let dodged = instruction::with_profiles(resolve.clone(), &wallets[..1]);
+    assert_custom_error(env.process(&[dodged], &[&players[1]]).await, RpsError::MissingProfile);
+
+    env.process(&[instruction::with_profiles(resolve, &wallets)], &[&players[1]]).await.unwrap();
+    assert_eq!(rating(&mut env, &wallets[0]).await, INITIAL_RATING + 16);
+    assert_eq!(rating(&mut env, &wallets[1]).await, INITIAL_RATING - 8);
+    assert_eq!(rating(&mut env, &wallets[2]).await, INITIAL_RATING - 8);
+}
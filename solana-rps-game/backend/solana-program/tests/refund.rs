@@ -0,0 +1,76 @@
+mod common;
+
+use common::{assert_custom_error, start, PLAYER_LAMPORTS};
+use solana_program_test::tokio;
+use solana_rps_game::{error::RpsError, instruction, GameState};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+const ENTRY_FEE: u64 = 1_000_000;
+const TIMEOUT: u64 = 300;
+
+#[tokio::test]
+async fn timed_out_lobby_refunds_every_joiner() {
+    let (mut env, players) = start(3).await;
+    let treasury = Pubkey::new_unique();
+    env.initialize_config(0, treasury, 86_400).await;
+
+    let (game, vault) = env.create_sol_game(&players[0], ENTRY_FEE, TIMEOUT).await;
+    env.join_sol_game(&players[1], &game).await;
+
+    let vault_rent = env.lamports(&vault).await - 2 * ENTRY_FEE;
+    assert_eq!(env.lamports(&players[1].pubkey()).await, PLAYER_LAMPORTS - ENTRY_FEE);
+
+    // Refunds are unavailable while the lobby is still open
+    let early_refund = instruction::claim_refund(&env.program_id, &players[1].pubkey(), &game, None);
+    let result = env.process(&[early_refund], &[&players[1]]).await;
+    assert_custom_error(result, RpsError::GameNotCancelled);
+
+    // The lobby cannot be cancelled before the timeout
+    let early_timeout = instruction::resolve_timeout(&env.program_id, &players[2].pubkey(), &game);
+    let result = env.process(&[early_timeout], &[&players[2]]).await;
+    assert_custom_error(result, RpsError::TimeoutNotReached);
+
+    env.advance_clock(TIMEOUT as i64).await;
+    let timeout = instruction::resolve_timeout(&env.program_id, &players[2].pubkey(), &game);
+    env.process(&[timeout], &[&players[2]]).await.unwrap();
+    assert_eq!(env.game(&game).await.state, GameState::Cancelled);
+
+    // Host and joiner both get their entry fee back
+    let game_rent = env.lamports(&game).await;
+    for player in &players[0..2] {
+        let refund = instruction::claim_refund(&env.program_id, &player.pubkey(), &game, None);
+        env.process(&[refund], &[player]).await.unwrap();
+    }
+
+    assert_eq!(env.lamports(&players[1].pubkey()).await, PLAYER_LAMPORTS);
+    assert_eq!(
+        env.lamports(&players[0].pubkey()).await,
+        PLAYER_LAMPORTS - game_rent - vault_rent
+    );
+    assert_eq!(env.lamports(&vault).await, vault_rent);
+    assert_eq!(env.game(&game).await.game_pot, 0);
+
+    // A second refund and a refund for an outsider are both rejected
+    let double_refund = instruction::claim_refund(&env.program_id, &players[1].pubkey(), &game, None);
+    let result = env.process(&[double_refund], &[&players[1]]).await;
+    assert_custom_error(result, RpsError::AlreadyClaimed);
+
+    let outsider_refund = instruction::claim_refund(&env.program_id, &players[2].pubkey(), &game, None);
+    let result = env.process(&[outsider_refund], &[&players[2]]).await;
+    assert_custom_error(result, RpsError::PlayerNotInGame);
+
+    // With every refund paid the game can be closed and the host gets all rent back
+    let close = instruction::close_game(
+        &env.program_id,
+        &players[2].pubkey(),
+        &game,
+        &players[0].pubkey(),
+        &treasury,
+        None,
+    );
+    env.process(&[close], &[&players[2]]).await.unwrap();
+
+    assert_eq!(env.lamports(&players[0].pubkey()).await, PLAYER_LAMPORTS);
+    assert_eq!(env.lamports(&game).await, 0);
+    assert_eq!(env.lamports(&vault).await, 0);
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    error::RpsError,
+    instruction::{self, GameSettings},
+    rules::{RuleSet, MAX_CHOICES},
+    Choice, GameState,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const ENTRY_FEE: u64 = 1_000_000;
+
+async fn create_game(env: &mut TestEnv, players: &[Keypair], rule_set: RuleSet) -> Pubkey {
+    let game = Keypair::new();
+    let initialize = env.initialize_game(&players[0], &game, GameSettings { rule_set, ..game_settings(ENTRY_FEE) });
+    env.process(&[initialize], &[&players[0], &game]).await.unwrap();
+    env.join_sol_game(&players[1], &game.pubkey()).await;
+    env.join_sol_game(&players[2], &game.pubkey()).await;
+    game.pubkey()
+}
+
+#[test]
+fn lizard_spock_follows_the_usual_rules() {
+    let rules = RuleSet::LizardSpock;
+    rules.validate().unwrap();
+
+    let beats = [
+        (Choice::ROCK, Choice::SCISSORS),
+        (Choice::ROCK, Choice::LIZARD),
+        (Choice::PAPER, Choice::ROCK),
+        (Choice::PAPER, Choice::SPOCK),
+        (Choice::SCISSORS, Choice::PAPER),
+        (Choice::SCISSORS, Choice::LIZARD),
+        (Choice::LIZARD, Choice::PAPER),
+        (Choice::LIZARD, Choice::SPOCK),
+        (Choice::SPOCK, Choice::ROCK),
+        (Choice::SPOCK, Choice::SCISSORS),
+    ];
+    for (winner, loser) in beats {
+        assert!(rules.beats(&winner, &loser));
+        assert!(!rules.beats(&loser, &winner));
+    }
+
+    assert!(!rules.beats(&Choice::SPOCK, &Choice::SPOCK));
+    assert!(!rules.beats(&Choice::ROCK, &Choice::NONE));
+    assert!(!rules.is_valid_choice(&Choice::new(6)));
+}
+
+#[test]
+fn custom_rule_sets_must_be_balanced_tournaments() {
+    RuleSet::Classic.validate().unwrap();
+
+    // Seven choices where each beats the next three around the circle
+    let cyclic: Vec<u16> = (0..7)
+        .map(|i| (1..=3).map(|step| 1u16 << ((i + step) % 7)).sum())
+        .collect();
+    RuleSet::Custom(cyclic.clone()).validate().unwrap();
+
+    let mut lopsided = cyclic.clone();
+    lopsided[0] |= 1 << 4;
+    lopsided[4] &= !1;
+
+    let mut self_beating = cyclic;
+    self_beating[0] |= 1;
+
+    let invalid = [
+        vec![0b110, 0b000, 0b010],              // Unbalanced
+        vec![0b0010, 0b0100, 0b1000, 0b0001],   // Even number of choices
+        vec![0b10, 0b01],                       // Too few
+        vec![0; MAX_CHOICES + 2],               // Too many
+        lopsided,
+        self_beating,
+    ];
+    for masks in invalid {
+        assert!(RuleSet::Custom(masks).validate().is_err());
+    }
+}
+
+#[tokio::test]
+async fn lizard_spock_games_score_the_extra_choices() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = create_game(&mut env, &players, RuleSet::LizardSpock).await;
+
+    // Spock beats Scissors and Rock
+    let all: Vec<&Keypair> = players.iter().collect();
+    env.play_round(&game, &all, &[Choice::SPOCK, Choice::SCISSORS, Choice::ROCK]).await;
+
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert_eq!(state.players[0].score, 2);
+    assert_eq!(state.players[0].payout, 3 * ENTRY_FEE);
+}
+
+#[tokio::test]
+async fn choices_outside_the_rule_set_cannot_be_revealed() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+    let game = create_game(&mut env, &players, RuleSet::Classic).await;
+
+    let salt = [4u8; 32];
+    for player in &players {
+        let committed_choice =
+            compute_commitment(&game, 1, &player.pubkey(), choice_byte(&Choice::LIZARD).unwrap(), &salt);
+        let commit = instruction::commit_choice(&env.program_id, &player.pubkey(), &game, committed_choice);
+        env.process(&[commit], &[player]).await.unwrap();
+    }
+
+    let reveal = instruction::reveal_choice(&env.program_id, &players[0].pubkey(), &game, Choice::LIZARD, salt);
+    assert_custom_error(env.process(&[reveal], &[&players[0]]).await, RpsError::InvalidChoice);
+}
+
+#[tokio::test]
+async fn invalid_rule_sets_are_rejected_at_creation() {
+    let (mut env, players) = start(1).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    let game = Keypair::new();
+    let rule_set = RuleSet::Custom(vec![0b110, 0b000, 0b010]);
+    let initialize = env.initialize_game(&players[0], &game, GameSettings { rule_set, ..game_settings(ENTRY_FEE) });
+    assert_custom_error(env.process(&[initialize], &[&players[0], &game]).await, RpsError::InvalidRuleSet);
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::{game_settings, start};
+use solana_program_test::tokio;
+use solana_rps_game::{instruction::GameSettings, rules::RuleSet, scoring::ScoringModel, Choice, GameState};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const R: Option<Choice> = Some(Choice::ROCK);
+const P: Option<Choice> = Some(Choice::PAPER);
+const S: Option<Choice> = Some(Choice::SCISSORS);
+const NO_REVEAL: Option<Choice> = Some(Choice::NONE);
+const OUT: Option<Choice> = None;
+
+fn points(model: ScoringModel, choices: &[Option<Choice>]) -> Vec<u8> {
+    model.round_points(&RuleSet::Classic, choices)
+}
+
+#[test]
+fn pairwise_scores_every_matchup() {
+    // A lone Rock beats all three Scissors
+    assert_eq!(points(ScoringModel::Pairwise, &[R, S, S, S]), vec![3, 0, 0, 0]);
+    assert_eq!(points(ScoringModel::Pairwise, &[R, P, S, R]), vec![1, 2, 1, 1]);
+    assert_eq!(points(ScoringModel::Pairwise, &[R, R, R]), vec![0, 0, 0]);
+
+    // Not revealing loses to any revealed choice
+    assert_eq!(points(ScoringModel::Pairwise, &[R, NO_REVEAL, S]), vec![2, 0, 1]);
+    assert_eq!(points(ScoringModel::Pairwise, &[NO_REVEAL, NO_REVEAL]), vec![0, 0]);
+
+    // Seats out of the game neither score nor concede points
+    assert_eq!(points(ScoringModel::Pairwise, &[R, OUT, S]), vec![1, 0, 0]);
+}
+
+#[test]
+fn round_winners_score_only_with_two_distinct_choices() {
+    assert_eq!(points(ScoringModel::RoundWinners, &[R, S, S, S]), vec![1, 0, 0, 0]);
+    assert_eq!(points(ScoringModel::RoundWinners, &[S, R, R, S]), vec![0, 1, 1, 0]);
+
+    // A mixed round and a unanimous one score nobody
+    assert_eq!(points(ScoringModel::RoundWinners, &[R, P, S, R]), vec![0, 0, 0, 0]);
+    assert_eq!(points(ScoringModel::RoundWinners, &[P, P, P]), vec![0, 0, 0]);
+
+    // A unanimous choice still beats players who never revealed
+    assert_eq!(points(ScoringModel::RoundWinners, &[R, R, NO_REVEAL]), vec![1, 1, 0]);
+    assert_eq!(points(ScoringModel::RoundWinners, &[R, NO_REVEAL, S]), vec![1, 0, 0]);
+    assert_eq!(points(ScoringModel::RoundWinners, &[NO_REVEAL, NO_REVEAL, NO_REVEAL]), vec![0, 0, 0]);
+
+    // Seats out of the game do not count as a third choice
+    assert_eq!(points(ScoringModel::RoundWinners, &[P, OUT, R, S]), vec![0, 0, 0, 0]);
+    assert_eq!(points(ScoringModel::RoundWinners, &[P, OUT, R]), vec![1, 0, 0]);
+
+    // The winning side follows the game's rule set
+    let spock = ScoringModel::RoundWinners.round_points(
+        &RuleSet::LizardSpock,
+        &[Some(Choice::SPOCK), R, Some(Choice::SPOCK)],
+    );
+    assert_eq!(spock, vec![1, 0, 1]);
+}
+
+#[test]
+fn majority_loses_rewards_everyone_outside_the_most_common_choice() {
+    assert_eq!(points(ScoringModel::MajorityLoses, &[R, S, S, S]), vec![1, 0, 0, 0]);
+    assert_eq!(points(ScoringModel::MajorityLoses, &[R, R, P, S]), vec![0, 0, 1, 1]);
+
+    // Without a single most common choice nobody scores
+    assert_eq!(points(ScoringModel::MajorityLoses, &[R, R, P, P]), vec![0, 0, 0, 0]);
+    assert_eq!(points(ScoringModel::MajorityLoses, &[R, P, S]), vec![0, 0, 0]);
+    assert_eq!(points(ScoringModel::MajorityLoses, &[S, S, S]), vec![0, 0, 0]);
+
+    // Unrevealed and eliminated seats never score and do not count towards the majority
+    assert_eq!(points(ScoringModel::MajorityLoses, &[R, R, S, NO_REVEAL]), vec![0, 0, 1, 0]);
+    assert_eq!(points(ScoringModel::MajorityLoses, &[R, R, OUT, P]), vec![0, 0, 0, 1]);
+    assert_eq!(points(ScoringModel::MajorityLoses, &[R, NO_REVEAL, NO_REVEAL]), vec![0, 0, 0]);
+}
+
+#[tokio::test]
+async fn games_score_rounds_with_their_model() {
+    let (mut env, players) = start(3).await;
+    env.initialize_config(0, Pubkey::new_unique(), 86_400).await;
+
+    let game = Keypair::new();
+    let settings = GameSettings {
+        scoring_model: ScoringModel::MajorityLoses,
+        ..game_settings(1_000_000)
+    };
+    let initialize = env.initialize_game(&players[0], &game, settings);
+    env.process(&[initialize], &[&players[0], &game]).await.unwrap();
+    let game = game.pubkey();
+    env.join_sol_game(&players[1], &game).await;
+    env.join_sol_game(&players[2], &game).await;
+
+    // Under pairwise points the two Rocks would win; here they are the majority
+    let all: Vec<&Keypair> = players.iter().collect();
+    env.play_round(&game, &all, &[Choice::ROCK, Choice::ROCK, Choice::SCISSORS]).await;
+
+    let state = env.game(&game).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert_eq!(state.scoring_model, ScoringModel::MajorityLoses);
+    assert_eq!(state.players.iter().map(|p| p.score).collect::<Vec<_>>(), vec![0, 0, 1]);
+    assert_eq!(state.players[2].payout, 3_000_000);
+}
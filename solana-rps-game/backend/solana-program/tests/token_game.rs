@@ -0,0 +1,170 @@
+mod common;
+
+use common::{assert_custom_error, game_settings, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    error::RpsError,
+    find_vault_address,
+    instruction,
+    Choice, GameState,
+};
+use solana_sdk::{
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+
+const ENTRY_FEE: u64 = 100;
+const MINTED: u64 = 1_000;
+const FEE_BASIS_POINTS: u16 = 500;
+
+async fn create_mint(env: &mut TestEnv, authority: &Keypair) -> Pubkey {
+    let mint = Keypair::new();
+    let instructions = [
+        system_instruction::create_account(
+            &env.payer(),
+            &mint.pubkey(),
+            Rent::default().minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &authority.pubkey(),
+            None,
+            0,
+        )
+        .unwrap(),
+    ];
+    env.process(&instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+async fn fund_player(env: &mut TestEnv, mint: &Pubkey, authority: &Keypair, player: &Pubkey) -> Pubkey {
+    let token_account = get_associated_token_address(player, mint);
+    let instructions = [
+        create_associated_token_account(&env.payer(), player, mint, &spl_token::id()),
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            mint,
+            &token_account,
+            &authority.pubkey(),
+            &[],
+            MINTED,
+        )
+        .unwrap(),
+    ];
+    env.process(&instructions, &[authority]).await.unwrap();
+    token_account
+}
+
+async fn token_balance(env: &mut TestEnv, token_account: &Pubkey) -> u64 {
+    let account = env
+        .context
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+#[tokio::test]
+async fn token_game_collects_fees_and_pays_winner() {
+    let (mut env, players) = start(3).await;
+    let mint_authority = Keypair::new();
+    let mint = create_mint(&mut env, &mint_authority).await;
+
+    let mut token_accounts = Vec::new();
+    for player in &players {
+        token_accounts.push(fund_player(&mut env, &mint, &mint_authority, &player.pubkey()).await);
+    }
+
+    let treasury = Keypair::new();
+    let treasury_token_account = fund_player(&mut env, &mint, &mint_authority, &treasury.pubkey()).await;
+    env.initialize_config(FEE_BASIS_POINTS, treasury.pubkey(), 86_400).await;
+
+    let game = Keypair::new();
+    let (vault, _) = find_vault_address(&game.pubkey(), &env.program_id);
+    let vault_token_account = get_associated_token_address(&vault, &mint);
+
+    // Host creates a three player token game and pays the first entry fee
+    let initialize = instruction::initialize_game(
+        &env.program_id,
+        &players[0].pubkey(),
+        &game.pubkey(),
+        Some(&mint),
+        game_settings(ENTRY_FEE),
+    );
+    env.process(&[initialize], &[&players[0], &game]).await.unwrap();
+
+    let state = env.game(&game.pubkey()).await;
+    assert_eq!(state.token_mint, mint);
+    assert_eq!(token_balance(&mut env, &vault_token_account).await, ENTRY_FEE);
+
+    for player in &players[1..] {
+        let join = instruction::join_game(&env.program_id, &player.pubkey(), &game.pubkey(), Some(&mint));
+        env.process(&[join], &[player]).await.unwrap();
+    }
+
+    assert_eq!(token_balance(&mut env, &vault_token_account).await, 3 * ENTRY_FEE);
+    assert_eq!(token_balance(&mut env, &token_accounts[1]).await, MINTED - ENTRY_FEE);
+    assert_eq!(env.game(&game.pubkey()).await.state, GameState::CommitPhase);
+
+    // Host throws Rock against two Scissors and wins the only round
+    let choices = [Choice::ROCK, Choice::SCISSORS, Choice::SCISSORS];
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.play_round(&game.pubkey(), &signers, &choices).await;
+
+    // The protocol fee is taken out of the pot when the game settles
+    let pot = 3 * ENTRY_FEE;
+    let fee = pot * FEE_BASIS_POINTS as u64 / 10_000;
+    let state = env.game(&game.pubkey()).await;
+    assert_eq!(state.state, GameState::Finished);
+    assert_eq!(state.fees_owed, fee);
+    assert_eq!(state.players[0].payout, pot - fee);
+    assert_eq!(state.game_pot, 0);
+
+    let program_id = env.program_id;
+    let claim = |player: &Keypair| {
+        instruction::claim_winnings(&program_id, &player.pubkey(), &game.pubkey(), Some(&mint))
+    };
+
+    // A loser cannot claim, the winner takes the pot minus fees in tokens
+    let losing_claim = claim(&players[1]);
+    let result = env.process(&[losing_claim], &[&players[1]]).await;
+    assert_custom_error(result, RpsError::NotAWinner);
+
+    let winning_claim = claim(&players[0]);
+    env.process(&[winning_claim], &[&players[0]]).await.unwrap();
+
+    let repeated_claim = claim(&players[0]);
+    let result = env.process(&[repeated_claim], &[&players[0]]).await;
+    assert_custom_error(result, RpsError::AlreadyClaimed);
+
+    assert_eq!(
+        token_balance(&mut env, &token_accounts[0]).await,
+        MINTED - ENTRY_FEE + pot - fee
+    );
+    assert_eq!(token_balance(&mut env, &vault_token_account).await, fee);
+
+    // The treasury sweeps the fee into its own token account
+    let withdraw = instruction::withdraw_fees(
+        &program_id,
+        &treasury.pubkey(),
+        &game.pubkey(),
+        &treasury.pubkey(),
+        Some(&mint),
+    );
+    env.process(&[withdraw], &[&treasury]).await.unwrap();
+
+    assert_eq!(token_balance(&mut env, &treasury_token_account).await, MINTED + fee);
+    assert_eq!(token_balance(&mut env, &vault_token_account).await, 0);
+    assert_eq!(env.game(&game.pubkey()).await.fees_owed, 0);
+}
@@ -0,0 +1,330 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use common::{assert_custom_error, setup, start, TestEnv};
+use solana_program_test::tokio;
+use solana_rps_game::{
+    commitment::{choice_byte, compute_commitment},
+    error::RpsError,
+    instruction,
+    randomness::GameRng,
+    tournament::{find_match_address, find_tournament_vault_address, Tournament, TournamentState},
+    Choice, GameState,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const BUY_IN: u64 = 1_000_000;
+const REGISTRATION_TIMEOUT: u64 = 600;
+
+async fn create_tournament(env: &mut TestEnv, organizer: &Keypair, max_entrants: u8, place_bps: Vec<u16>) -> Pubkey {
+    let tournament = Keypair::new();
+    let create = instruction::create_tournament(
+        &env.program_id,
+        &organizer.pubkey(),
+        &tournament.pubkey(),
+        max_entrants,
+        BUY_IN,
+        1,
+        60,
+        60,
+        place_bps,
+        REGISTRATION_TIMEOUT,
+    );
+    env.process(&[create], &[organizer, &tournament]).await.unwrap();
+    tournament.pubkey()
+}
+
+async fn register(env: &mut TestEnv, tournament: &Pubkey, entrants: &[Keypair]) {
+    for entrant in entrants {
+        let register = instruction::register_for_tournament(&env.program_id, &entrant.pubkey(), tournament);
+        env.process(&[register], &[entrant]).await.unwrap();
+    }
+}
+
+async fn load_tournament(env: &mut TestEnv, tournament: &Pubkey) -> Tournament {
+    let account = env.context.banks_client.get_account(*tournament).await.unwrap().unwrap();
+    Tournament::deserialize(&mut &account.data[..]).unwrap()
+}
+
+// Open the match, play one round with the given choices and record the result
+async fn play_match(
+    env: &mut TestEnv,
+    tournament: &Pubkey,
+    round: u8,
+    index: u8,
+    players: [&Keypair; 2],
+    choices: [Choice; 2],
+) {
+    let open = instruction::open_tournament_match(&env.program_id, &env.payer(), tournament, round, index);
+    env.process(&[open], &[]).await.unwrap();
+
+    let (game, _) = find_match_address(tournament, round, index, &env.program_id);
+    env.play_round(&game, &players, &choices).await;
+
+    let treasury = env.treasury().await;
+    let advance = instruction::advance_tournament(&env.program_id, &env.payer(), tournament, &game, &env.payer(), &treasury);
+    env.process(&[advance], &[]).await.unwrap();
+}
+
+async fn claim(env: &mut TestEnv, tournament: &Pubkey, entrant: &Keypair) -> u64 {
+    let before = env.lamports(&entrant.pubkey()).await;
+    let claim = instruction::claim_tournament_payout(&env.program_id, &entrant.pubkey(), tournament);
+    env.process(&[claim], &[entrant]).await.unwrap();
+    env.lamports(&entrant.pubkey()).await - before
+}
+
+#[tokio::test]
+async fn bracket_pays_the_pool_by_finishing_place() {
+    let (mut env, entrants) = setup(5).await;
+    let organizer = &entrants[4];
+    let tournament = create_tournament(&mut env, organizer, 4, vec![6_000, 3_000, 1_000]).await;
+    register(&mut env, &tournament, &entrants[..4]).await;
+
+    let state = load_tournament(&mut env, &tournament).await;
+    assert_eq!(state.state, TournamentState::InProgress);
+    assert_eq!(state.prize_pool, 4 * BUY_IN);
+
+    // Semifinals pair entrants in registration order
+    play_match(&mut env, &tournament, 0, 0, [&entrants[0], &entrants[1]], [Choice::ROCK, Choice::SCISSORS]).await;
+    play_match(&mut env, &tournament, 0, 1, [&entrants[2], &entrants[3]], [Choice::PAPER, Choice::ROCK]).await;
+
+    let state = load_tournament(&mut env, &tournament).await;
+    assert_eq!(state.bracket_round, 1);
+    assert_eq!(state.remaining, vec![0, 2]);
+
+    // Recorded matches are closed
+    let (semifinal, _) = find_match_address(&tournament, 0, 0, &env.program_id);
+    assert!(env.context.banks_client.get_account(semifinal).await.unwrap().is_none());
+
+    play_match(&mut env, &tournament, 1, 0, [&entrants[0], &entrants[2]], [Choice::SCISSORS, Choice::PAPER]).await;
+
+    let state = load_tournament(&mut env, &tournament).await;
+    assert_eq!(state.state, TournamentState::Finished);
+    assert_eq!(state.prize_pool, 0);
+
+    // Champion 60%, runner-up 30%, the semifinal losers split 10%
+    assert_eq!(claim(&mut env, &tournament, &entrants[0]).await, 2_400_000);
+    assert_eq!(claim(&mut env, &tournament, &entrants[2]).await, 1_200_000);
+    assert_eq!(claim(&mut env, &tournament, &entrants[1]).await, 200_000);
+    assert_eq!(claim(&mut env, &tournament, &entrants[3]).await, 200_000);
+
+    let claim_again = instruction::claim_tournament_payout(&env.program_id, &entrants[0].pubkey(), &tournament);
+    assert_custom_error(env.process(&[claim_again], &[&entrants[0]]).await, RpsError::AlreadyClaimed);
+}
+
+#[tokio::test]
+async fn player_who_never_reveals_loses_the_match() {
+    let (mut env, entrants) = setup(2).await;
+    let tournament = create_tournament(&mut env, &entrants[0], 2, vec![10_000]).await;
+    register(&mut env, &tournament, &entrants).await;
+
+    let open = instruction::open_tournament_match(&env.program_id, &env.payer(), &tournament, 0, 0);
+    env.process(&[open], &[]).await.unwrap();
+    let (game, _) = find_match_address(&tournament, 0, 0, &env.program_id);
+
+    // Both commit but only the second entrant reveals
+    let salt = [7u8; 32];
+    for entrant in &entrants {
+        let commitment = compute_commitment(&game, 1, &entrant.pubkey(), choice_byte(&Choice::ROCK).unwrap(), &salt);
+        let commit = instruction::commit_choice(&env.program_id, &entrant.pubkey(), &game, commitment);
+        env.process(&[commit], &[entrant]).await.unwrap();
+    }
+    let reveal = instruction::reveal_choice(&env.program_id, &entrants[1].pubkey(), &game, Choice::ROCK, salt);
+    env.process(&[reveal], &[&entrants[1]]).await.unwrap();
+
+    // The match cannot be recorded until it is resolved
+    let treasury = env.treasury().await;
+    let advance =
+        instruction::advance_tournament(&env.program_id, &env.payer(), &tournament, &game, &env.payer(), &treasury);
+    assert_custom_error(env.process(std::slice::from_ref(&advance), &[]).await, RpsError::MatchNotFinished);
+
+    env.advance_clock(61).await;
+    let resolve = instruction::resolve_timeout(&env.program_id, &env.payer(), &game);
+    let resolve = env.with_human_profiles(resolve, &game).await;
+    env.process(&[resolve], &[]).await.unwrap();
+    assert_eq!(env.game(&game).await.state, GameState::Finished);
+
+    env.process(&[advance], &[]).await.unwrap();
+
+    let state = load_tournament(&mut env, &tournament).await;
+    assert_eq!(state.state, TournamentState::Finished);
+    assert_eq!(state.entrants[1].payout, 2 * BUY_IN);
+    assert_eq!(state.entrants[0].payout, 0);
+}
+
+#[tokio::test]
+async fn tied_match_is_drawn_from_the_finished_match() {
+    let (mut env, entrants) = setup(2).await;
+    let tournament = create_tournament(&mut env, &entrants[0], 2, vec![10_000]).await;
+    register(&mut env, &tournament, &entrants).await;
+
+    let open = instruction::open_tournament_match(&env.program_id, &env.payer(), &tournament, 0, 0);
+    env.process(&[open], &[]).await.unwrap();
+    let (game, _) = find_match_address(&tournament, 0, 0, &env.program_id);
+    let both: Vec<&Keypair> = entrants.iter().collect();
+    env.play_round(&game, &both, &[Choice::ROCK, Choice::ROCK]).await;
+
+    // The winner is fixed once the match is finished, however late it is recorded
+    let entropy = env.game(&game).await.entropy;
+    let first_wins = GameRng::new(&entropy, &game.to_bytes()).below(2) == 0;
+    env.advance_clock(3_600).await;
+
+    let treasury = env.treasury().await;
+    let advance =
+        instruction::advance_tournament(&env.program_id, &env.payer(), &tournament, &game, &env.payer(), &treasury);
+    env.process(&[advance], &[]).await.unwrap();
+
+    let state = load_tournament(&mut env, &tournament).await;
+    let champion = if first_wins { 0 } else { 1 };
+    assert_eq!(state.entrants[champion].payout, 2 * BUY_IN);
+    assert_eq!(state.entrants[1 - champion].payout, 0);
+}
+
+#[tokio::test]
+async fn player_who_never_commits_forfeits_the_match() {
+    let (mut env, entrants) = setup(2).await;
+    let tournament = create_tournament(&mut env, &entrants[0], 2, vec![10_000]).await;
+    register(&mut env, &tournament, &entrants).await;
+
+    let open = instruction::open_tournament_match(&env.program_id, &env.payer(), &tournament, 0, 0);
+    env.process(&[open], &[]).await.unwrap();
+    let (game, _) = find_match_address(&tournament, 0, 0, &env.program_id);
+
+    // Only the second entrant commits; a 0-0 finish must not go to a draw
+    let commitment = compute_commitment(&game, 1, &entrants[1].pubkey(), choice_byte(&Choice::ROCK).unwrap(), &[7u8; 32]);
+    let commit = instruction::commit_choice(&env.program_id, &entrants[1].pubkey(), &game, commitment);
+    env.process(&[commit], &[&entrants[1]]).await.unwrap();
+
+    env.advance_clock(61).await;
+    let resolve = instruction::resolve_timeout(&env.program_id, &env.payer(), &game);
+    let resolve = env.with_human_profiles(resolve, &game).await;
+    env.process(&[resolve], &[]).await.unwrap();
+
+    let match_state = env.game(&game).await;
+    assert_eq!(match_state.state, GameState::Finished);
+    assert!(match_state.players[0].eliminated);
+
+    let treasury = env.treasury().await;
+    let advance =
+        instruction::advance_tournament(&env.program_id, &env.payer(), &tournament, &game, &env.payer(), &treasury);
+    env.process(&[advance], &[]).await.unwrap();
+
+    let state = load_tournament(&mut env, &tournament).await;
+    assert_eq!(state.state, TournamentState::Finished);
+    assert_eq!(state.entrants[1].payout, 2 * BUY_IN);
+    assert_eq!(state.entrants[0].payout, 0);
+}
+
+#[tokio::test]
+async fn cancelled_tournament_refunds_buy_ins() {
+    let (mut env, entrants) = setup(3).await;
+    let organizer = &entrants[2];
+
+    // Prize tables must sum to 100% over at most one share per place
+    let rejected = Keypair::new();
+    let bad_table = instruction::create_tournament(
+        &env.program_id,
+        &organizer.pubkey(),
+        &rejected.pubkey(),
+        4,
+        BUY_IN,
+        1,
+        60,
+        60,
+        vec![5_000, 3_000, 1_000, 1_000],
+        REGISTRATION_TIMEOUT,
+    );
+    assert_custom_error(env.process(&[bad_table], &[organizer, &rejected]).await, RpsError::InvalidPrizeTable);
+
+    let tournament = create_tournament(&mut env, organizer, 4, vec![7_000, 3_000]).await;
+    register(&mut env, &tournament, &entrants[..2]).await;
+
+    // Only the organizer can cancel while registration is open
+    let cancel = instruction::cancel_tournament(&env.program_id, &entrants[0].pubkey(), &tournament);
+    assert_custom_error(env.process(&[cancel], &[&entrants[0]]).await, RpsError::NotTournamentOrganizer);
+
+    let cancel = instruction::cancel_tournament(&env.program_id, &organizer.pubkey(), &tournament);
+    env.process(&[cancel], &[organizer]).await.unwrap();
+
+    let register_late = instruction::register_for_tournament(&env.program_id, &organizer.pubkey(), &tournament);
+    assert_custom_error(env.process(&[register_late], &[organizer]).await, RpsError::TournamentNotOpen);
+
+    assert_eq!(claim(&mut env, &tournament, &entrants[0]).await, BUY_IN);
+    assert_eq!(claim(&mut env, &tournament, &entrants[1]).await, BUY_IN);
+
+    // Only the vault's rent is left behind
+    let (vault, _) = find_tournament_vault_address(&tournament, &env.program_id);
+    let rent_floor = env.context.banks_client.get_rent().await.unwrap().minimum_balance(0);
+    assert_eq!(env.lamports(&vault).await, rent_floor);
+}
+
+#[tokio::test]
+async fn anyone_can_cancel_once_registration_times_out() {
+    let (mut env, entrants) = setup(3).await;
+    let organizer = &entrants[2];
+    let tournament = create_tournament(&mut env, organizer, 4, vec![10_000]).await;
+    register(&mut env, &tournament, &entrants[..1]).await;
+
+    env.advance_clock(REGISTRATION_TIMEOUT as i64).await;
+
+    // Registration is closed, so the bracket can no longer fill
+    let register_late = instruction::register_for_tournament(&env.program_id, &entrants[1].pubkey(), &tournament);
+    assert_custom_error(env.process(&[register_late], &[&entrants[1]]).await, RpsError::TournamentNotOpen);
+
+    let cancel = instruction::cancel_tournament(&env.program_id, &entrants[1].pubkey(), &tournament);
+    env.process(&[cancel], &[&entrants[1]]).await.unwrap();
+    assert_eq!(load_tournament(&mut env, &tournament).await.state, TournamentState::Cancelled);
+    assert_eq!(claim(&mut env, &tournament, &entrants[0]).await, BUY_IN);
+}
+
+#[tokio::test]
+async fn tournaments_and_matches_cannot_stand_in_for_each_other() {
+    let (mut env, entrants) = setup(3).await;
+    let tournament = create_tournament(&mut env, &entrants[0], 2, vec![10_000]).await;
+    register(&mut env, &tournament, &entrants[..2]).await;
+
+    let open = instruction::open_tournament_match(&env.program_id, &env.payer(), &tournament, 0, 0);
+    env.process(&[open], &[]).await.unwrap();
+    let (game, _) = find_match_address(&tournament, 0, 0, &env.program_id);
+
+    let commit = instruction::commit_choice(&env.program_id, &entrants[0].pubkey(), &tournament, [1; 32]);
+    assert_custom_error(env.process(&[commit], &[&entrants[0]]).await, RpsError::InvalidGameAccount);
+
+    let register = instruction::register_for_tournament(&env.program_id, &entrants[2].pubkey(), &game);
+    assert_custom_error(env.process(&[register], &[&entrants[2]]).await, RpsError::InvalidTournamentAccount);
+}
+
+#[tokio::test]
+async fn the_protocol_fee_comes_out_of_the_prize_pool() {
+    let (mut env, entrants) = start(3).await;
+    let treasury = entrants[2].pubkey();
+    env.initialize_config(500, treasury, 86_400).await;
+    let tournament = create_tournament(&mut env, &entrants[0], 2, vec![10_000]).await;
+
+    // The fee is fixed when the tournament is created
+    let payer = env.payer();
+    let update = instruction::update_config(&env.program_id, &payer, payer, 1_000, treasury, 86_400);
+    env.process(&[update], &[]).await.unwrap();
+
+    register(&mut env, &tournament, &entrants[..2]).await;
+    let open = instruction::open_tournament_match(&env.program_id, &payer, &tournament, 0, 0);
+    env.process(&[open], &[]).await.unwrap();
+    let (game, _) = find_match_address(&tournament, 0, 0, &env.program_id);
+    let players: Vec<&Keypair> = entrants[..2].iter().collect();
+    env.play_round(&game, &players, &[Choice::ROCK, Choice::SCISSORS]).await;
+
+    let elsewhere = Pubkey::new_unique();
+    let misdirected = instruction::advance_tournament(&env.program_id, &payer, &tournament, &game, &payer, &elsewhere);
+    assert_custom_error(env.process(&[misdirected], &[]).await, RpsError::InvalidTreasury);
+
+    let before = env.lamports(&treasury).await;
+    let advance = instruction::advance_tournament(&env.program_id, &payer, &tournament, &game, &payer, &treasury);
+    env.process(&[advance], &[]).await.unwrap();
+
+    let fee = 2 * BUY_IN * 500 / 10_000;
+    assert_eq!(env.lamports(&treasury).await - before, fee);
+    assert_eq!(claim(&mut env, &tournament, &entrants[0]).await, 2 * BUY_IN - fee);
+}